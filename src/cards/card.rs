@@ -36,7 +36,7 @@ pub fn get_value(card: Card) -> Card {
     card & VALUE_MASK
 }
 
-pub fn display_cards(cards: &Vec<Card>) -> String {
+pub fn display_cards(cards: &[Card]) -> String {
     cards
         .iter()
         .map(|&c| display_card(c))
@@ -74,7 +74,7 @@ pub fn display_card(card: Card) -> String {
     format!("{}{}", value_str, suit_str)
 }
 
-pub fn count_cards(cards: &Vec<Card>) -> [u8; 14] {
+pub fn count_cards(cards: &[Card]) -> [u8; 14] {
     let mut counts = [0; 14];
     for (idx, val) in counts.iter_mut().enumerate() {
         *val += cards.iter().filter(|&c| c & (1 << idx) != 0).count() as u8;
@@ -58,9 +58,9 @@ impl PartialEq for CardStack {
 
 impl Eq for CardStack {}
 
-impl Into<Vec<Card>> for CardStack {
-    fn into(self) -> Vec<Card> {
-        self.cards
+impl From<CardStack> for Vec<Card> {
+    fn from(stack: CardStack) -> Self {
+        stack.cards
     }
 }
 
@@ -84,8 +84,8 @@ const STRAIGHT_7: Card = EIGHT | NINE | TEN | JACK | QUEEN;
 const STRAIGHT_8: Card = NINE | TEN | JACK | QUEEN | KING;
 const HIGH_STRAIGHT: Card = TEN | JACK | QUEEN | KING | ACE;
 
-pub fn get_hand(cards: &Vec<Card>) -> Hand {
-    let mut cards = cards.clone();
+pub fn get_hand(cards: &[Card]) -> Hand {
+    let mut cards = cards.to_vec();
     sort_cards(&mut cards);
 
     let cards_mask = cards.iter().fold(0, |acc, &c| acc | c);
@@ -122,17 +122,17 @@ pub fn get_hand(cards: &Vec<Card>) -> Hand {
 
     let count = count_cards(&cards);
 
-    let is_four_of_a_kind = count.iter().any(|&c| c == 4);
+    let is_four_of_a_kind = count.contains(&4);
     if is_four_of_a_kind {
         return Hand::FourOfAKind;
     }
 
-    let is_full_house = count.iter().any(|&c| c == 3) && count.iter().any(|&c| c == 2);
+    let is_full_house = count.contains(&3) && count.contains(&2);
     if is_full_house {
         return Hand::FullHouse;
     }
 
-    let is_three_of_a_kind = count.iter().any(|&c| c == 3);
+    let is_three_of_a_kind = count.contains(&3);
     if is_three_of_a_kind {
         return Hand::ThreeOfAKind;
     }
@@ -142,7 +142,7 @@ pub fn get_hand(cards: &Vec<Card>) -> Hand {
         return Hand::TwoPair;
     }
 
-    let is_pair = count.iter().any(|&c| c == 2);
+    let is_pair = count.contains(&2);
     if is_pair {
         return Hand::Pair;
     }
@@ -150,7 +150,7 @@ pub fn get_hand(cards: &Vec<Card>) -> Hand {
     Hand::HighCard
 }
 
-pub fn value(cards: &Vec<Card>) -> Card {
+pub fn value(cards: &[Card]) -> Card {
     let mut value = 0;
 
     for card in cards {
@@ -164,7 +164,7 @@ pub fn value(cards: &Vec<Card>) -> Card {
     value
 }
 
-pub fn shuffle(cards: &mut Vec<Card>) {
+pub fn shuffle(cards: &mut [Card]) {
     cards.shuffle(&mut thread_rng());
 }
 
@@ -214,9 +214,9 @@ impl CardStack {
     }
 }
 
-pub fn sort_cards(cards: &mut Vec<Card>) {
+pub fn sort_cards(cards: &mut [Card]) {
     // group by card value and larger groups should be at the front
-    let count = count_cards(&cards);
+    let count = count_cards(cards);
     cards.sort_by(|a, b| {
         let a = get_value(*a);
         let b = get_value(*b);
@@ -229,7 +229,7 @@ pub fn sort_cards(cards: &mut Vec<Card>) {
             return b_count.cmp(&a_count);
         }
 
-        return b.cmp(&a);
+        b.cmp(&a)
     });
 }
 
@@ -1,2 +1,3 @@
 pub mod card;
 pub mod card_stack;
+pub mod rank;
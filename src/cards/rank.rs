@@ -0,0 +1,576 @@
+use crate::*;
+
+/// Showdown strength of a hand, a bigger rank always wins.
+///
+/// High hands keep the category in the bits above `CATEGORY_SHIFT` and the
+/// card ranks that break ties in four bit groups below it.
+/// Low hands are stored inverted so that the better low gets the bigger rank.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rank(pub u32);
+
+const CATEGORY_SHIFT: u32 = 20;
+const LOW_BASE: u32 = 1 << 24;
+
+/// Card values as bits, with the ace on bit 0 as well so it can play low
+const ACE_LOW: Card = 1;
+
+impl Rank {
+    /// Category of a high hand
+    pub fn hand(&self) -> Hand {
+        match self.0 >> CATEGORY_SHIFT {
+            0 => Hand::HighCard,
+            1 => Hand::Pair,
+            2 => Hand::TwoPair,
+            3 => Hand::ThreeOfAKind,
+            4 => Hand::Straight,
+            5 => Hand::Flush,
+            6 => Hand::FullHouse,
+            7 => Hand::FourOfAKind,
+            _ if self.0 & 0xF0000 == 13 << 16 => Hand::RoyalFlush,
+            _ => Hand::StraightFlush,
+        }
+    }
+}
+
+/// Index of the value bit, from 1 for a two to 13 for an ace
+pub(crate) fn value_index(card: Card) -> u32 {
+    get_value(card).trailing_zeros()
+}
+
+/// Packs up to five card indexes, most important first, below the category
+fn pack(category: u32, indexes: &[u32]) -> u32 {
+    let kickers = indexes
+        .iter()
+        .take(5)
+        .enumerate()
+        .fold(0, |acc, (position, &index)| {
+            acc | index << (16 - 4 * position as u32)
+        });
+
+    category << CATEGORY_SHIFT | kickers
+}
+
+/// Highest card index of five connected values in `mask`, the ace counts as high and low
+fn straight_high(mask: Card) -> Option<u32> {
+    let mask = if mask & ACE != 0 {
+        mask | ACE_LOW
+    } else {
+        mask
+    };
+
+    (4..=13)
+        .rev()
+        .find(|&high| (mask >> (high - 4)) & 0b11111 == 0b11111)
+}
+
+/// Card indexes set in `mask`, highest first
+fn indexes_of(mask: Card) -> Vec<u32> {
+    (1..=13).rev().filter(|&i| mask & (1 << i) != 0).collect()
+}
+
+/// Best five card high hand out of any number of cards
+pub fn high(cards: &[Card]) -> Rank {
+    let mut counts = [0u8; 14];
+    for &card in cards {
+        counts[value_index(card) as usize] += 1;
+    }
+
+    let value_mask = cards.iter().fold(0, |acc, &c| acc | get_value(c));
+
+    let flush = SUITS
+        .iter()
+        .map(|&suit| {
+            cards
+                .iter()
+                .filter(|&&c| get_suit(c) == suit)
+                .fold(0, |acc, &c| acc | get_value(c))
+        })
+        .find(|mask| mask.count_ones() >= 5);
+
+    if let Some(flush) = flush {
+        if let Some(high) = straight_high(flush) {
+            return Rank(pack(8, &[high]));
+        }
+    }
+
+    // indexes grouped by how often they appear, highest first
+    let with_count = |count: u8| -> Vec<u32> {
+        (1..=13u32)
+            .rev()
+            .filter(|&i| counts[i as usize] == count)
+            .collect()
+    };
+    let quads = with_count(4);
+    let trips = with_count(3);
+    let pairs = with_count(2);
+
+    let kickers = |used: &[u32], count: usize| -> Vec<u32> {
+        indexes_of(value_mask)
+            .into_iter()
+            .filter(|i| !used.contains(i))
+            .take(count)
+            .collect()
+    };
+
+    if let Some(&quad) = quads.first() {
+        let mut ranks = vec![quad];
+        ranks.extend(kickers(&ranks.clone(), 1));
+        return Rank(pack(7, &ranks));
+    }
+
+    if let Some(&trip) = trips.first() {
+        // the pair of a full house can come from a second set of trips
+        let pair = trips.iter().skip(1).chain(pairs.iter()).max();
+        if let Some(&pair) = pair {
+            return Rank(pack(6, &[trip, pair]));
+        }
+    }
+
+    if let Some(flush) = flush {
+        return Rank(pack(5, &indexes_of(flush)));
+    }
+
+    if let Some(high) = straight_high(value_mask) {
+        return Rank(pack(4, &[high]));
+    }
+
+    if let Some(&trip) = trips.first() {
+        let mut ranks = vec![trip];
+        ranks.extend(kickers(&ranks.clone(), 2));
+        return Rank(pack(3, &ranks));
+    }
+
+    if pairs.len() >= 2 {
+        let mut ranks = vec![pairs[0], pairs[1]];
+        ranks.extend(kickers(&ranks.clone(), 1));
+        return Rank(pack(2, &ranks));
+    }
+
+    if let Some(&pair) = pairs.first() {
+        let mut ranks = vec![pair];
+        ranks.extend(kickers(&ranks.clone(), 3));
+        return Rank(pack(1, &ranks));
+    }
+
+    Rank(pack(0, &indexes_of(value_mask)))
+}
+
+/// Every way to pick `count` cards out of `cards`
+pub fn combinations(cards: &[Card], count: usize) -> Vec<Vec<Card>> {
+    if count == 0 {
+        return vec![vec![]];
+    }
+
+    (0..cards.len())
+        .flat_map(|first| {
+            combinations(&cards[first + 1..], count - 1)
+                .into_iter()
+                .map(move |mut rest| {
+                    rest.insert(0, cards[first]);
+                    rest
+                })
+        })
+        .collect()
+}
+
+/// Best high hand using exactly two hole cards and three cards from the board
+pub fn omaha(hole: &[Card], board: &[Card]) -> Rank {
+    let boards = combinations(board, 3);
+
+    combinations(hole, 2)
+        .iter()
+        .flat_map(|hole| {
+            boards.iter().map(move |board| {
+                let cards: Vec<Card> = hole.iter().chain(board.iter()).copied().collect();
+                high(&cards)
+            })
+        })
+        .max()
+        .unwrap_or(Rank(0))
+}
+
+/// Card value for low hands, the ace is the lowest card
+fn low_value(card: Card) -> u32 {
+    match value_index(card) {
+        13 => 1,
+        index => index + 1,
+    }
+}
+
+/// Best five card ace-to-five low, straights and flushes don't count.
+/// Pairs only play when there are not five different values.
+pub fn ace_to_five_low(cards: &[Card]) -> Rank {
+    let mut counts = [0u8; 14];
+    for &card in cards {
+        counts[low_value(card) as usize] += 1;
+    }
+
+    // pick the lowest cards, each value once before any value is used twice
+    let mut picked: Vec<u32> = vec![];
+    for round in 1..=4u8 {
+        for value in 1..=13u32 {
+            if picked.len() < 5 && counts[value as usize] >= round {
+                picked.push(value);
+            }
+        }
+    }
+
+    let mut repeats = [0u8; 14];
+    picked.iter().for_each(|&v| repeats[v as usize] += 1);
+    let worst_group = *repeats.iter().max().unwrap_or(&0);
+    let category = match worst_group {
+        0 | 1 => 0,
+        2 if repeats.iter().filter(|&&r| r == 2).count() == 1 => 1,
+        2 => 2,
+        3 => 3,
+        _ => 4,
+    };
+
+    // compare paired cards first, then from the highest card down
+    picked.sort_by(|a, b| {
+        repeats[*b as usize]
+            .cmp(&repeats[*a as usize])
+            .then(b.cmp(a))
+    });
+
+    Rank(LOW_BASE - pack(category, &picked))
+}
+
+/// Ace-to-five low that only qualifies with five different cards of eight or lower
+pub fn eight_or_better(cards: &[Card]) -> Option<Rank> {
+    let values: Vec<u32> = cards.iter().map(|&c| low_value(c)).collect();
+    let qualifying = (1..=8).filter(|v| values.contains(v)).count();
+
+    if qualifying < 5 {
+        return None;
+    }
+
+    let low: Vec<Card> = cards
+        .iter()
+        .copied()
+        .filter(|&c| low_value(c) <= 8)
+        .collect();
+    Some(ace_to_five_low(&low))
+}
+
+/// Best deuce-to-seven low: the worst five card high hand wins.
+/// Aces are always high, so A-2-3-4-5 is no straight
+pub fn deuce_to_seven_low(cards: &[Card]) -> Rank {
+    let worst = combinations(cards, 5)
+        .iter()
+        .map(|five| {
+            let mask = five.iter().fold(0, |acc, &c| acc | get_value(c));
+            if mask != ACE | TWO | THREE | FOUR | FIVE {
+                return high(five);
+            }
+
+            let flush = five.iter().all(|&c| get_suit(c) == get_suit(five[0]));
+            let category = if flush { 5 } else { 0 };
+            Rank(pack(category, &indexes_of(mask)))
+        })
+        .min()
+        .unwrap_or(Rank(0));
+
+    Rank(LOW_BASE - worst.0)
+}
+
+/// Best eight or better low with exactly two hole cards and three from the board
+pub fn omaha_eight_or_better(hole: &[Card], board: &[Card]) -> Option<Rank> {
+    let boards = combinations(board, 3);
+
+    combinations(hole, 2)
+        .iter()
+        .flat_map(|hole| {
+            boards.iter().filter_map(move |board| {
+                let cards: Vec<Card> = hole.iter().chain(board.iter()).copied().collect();
+                eight_or_better(&cards)
+            })
+        })
+        .max()
+}
+
+/// Best badugi: the most cards of different suits and values, then the lowest high card.
+/// Aces are low.
+pub fn badugi(cards: &[Card]) -> Rank {
+    let len = cards.len();
+    let mut best: Option<(usize, Vec<u32>)> = None;
+
+    for subset in 1..(1u32 << len) {
+        let picked: Vec<Card> = (0..len)
+            .filter(|&i| subset & (1 << i) != 0)
+            .map(|i| cards[i])
+            .collect();
+
+        let suits = picked.iter().fold(0, |acc, &c| acc | get_suit(c));
+        let values = picked.iter().fold(0, |acc, &c| acc | get_value(c));
+        if suits.count_ones() as usize != picked.len()
+            || values.count_ones() as usize != picked.len()
+        {
+            continue;
+        }
+
+        let mut lows: Vec<u32> = picked.iter().map(|&c| low_value(c)).collect();
+        lows.sort_by(|a, b| b.cmp(a));
+
+        let better = match &best {
+            None => true,
+            Some((size, best_lows)) => {
+                picked.len() > *size || (picked.len() == *size && lows < *best_lows)
+            }
+        };
+
+        if better {
+            best = Some((picked.len(), lows));
+        }
+    }
+
+    let (size, lows) = best.unwrap_or((0, vec![]));
+    // more cards win, fewer cards would need a lower high card
+    Rank(((size as u32) << CATEGORY_SHIFT) | ((1 << CATEGORY_SHIFT) - 1 - pack(0, &lows)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_high_categories() {
+        let royal = [
+            HEART | ACE,
+            HEART | KING,
+            HEART | QUEEN,
+            HEART | JACK,
+            HEART | TEN,
+        ];
+        assert_eq!(high(&royal).hand(), Hand::RoyalFlush);
+
+        let wheel = [
+            HEART | ACE,
+            CLUB | TWO,
+            HEART | THREE,
+            SPADE | FOUR,
+            HEART | FIVE,
+        ];
+        assert_eq!(high(&wheel).hand(), Hand::Straight);
+
+        let full_house = [
+            HEART | TWO,
+            DIAMOND | TWO,
+            HEART | THREE,
+            DIAMOND | THREE,
+            SPADE | THREE,
+            HEART | ACE,
+            CLUB | TWO,
+        ];
+        assert_eq!(high(&full_house).hand(), Hand::FullHouse);
+
+        let flush = [
+            HEART | TWO,
+            HEART | NINE,
+            HEART | THREE,
+            HEART | KING,
+            HEART | SEVEN,
+            SPADE | SEVEN,
+        ];
+        assert_eq!(high(&flush).hand(), Hand::Flush);
+    }
+
+    #[test]
+    fn test_high_kickers() {
+        let aces_king = [
+            HEART | ACE,
+            SPADE | ACE,
+            CLUB | KING,
+            CLUB | FOUR,
+            DIAMOND | TWO,
+        ];
+        let aces_queen = [
+            HEART | ACE,
+            DIAMOND | ACE,
+            CLUB | QUEEN,
+            CLUB | JACK,
+            DIAMOND | TEN,
+        ];
+        assert!(high(&aces_king) > high(&aces_queen));
+
+        // a six high straight beats the wheel
+        let wheel = [
+            HEART | ACE,
+            CLUB | TWO,
+            HEART | THREE,
+            SPADE | FOUR,
+            HEART | FIVE,
+        ];
+        let six_high = [
+            CLUB | SIX,
+            CLUB | TWO,
+            HEART | THREE,
+            SPADE | FOUR,
+            HEART | FIVE,
+        ];
+        assert!(high(&six_high) > high(&wheel));
+
+        // only the best five cards play
+        let board = [
+            CLUB | ACE,
+            CLUB | ACE,
+            DIAMOND | KING,
+            DIAMOND | KING,
+            SPADE | QUEEN,
+        ];
+        let mut first = board.to_vec();
+        first.push(HEART | TWO);
+        let mut second = board.to_vec();
+        second.push(HEART | THREE);
+        assert_eq!(high(&first), high(&second));
+    }
+
+    #[test]
+    fn test_omaha_uses_two_hole_cards() {
+        let board = [
+            HEART | TWO,
+            HEART | NINE,
+            HEART | THREE,
+            HEART | KING,
+            SPADE | KING,
+        ];
+
+        // a single heart in the hand makes no flush
+        let one_heart = [HEART | ACE, CLUB | QUEEN, DIAMOND | JACK, CLUB | FOUR];
+        assert_eq!(omaha(&one_heart, &board).hand(), Hand::Pair);
+
+        let two_hearts = [HEART | ACE, HEART | QUEEN, DIAMOND | JACK, CLUB | FOUR];
+        assert_eq!(omaha(&two_hearts, &board).hand(), Hand::Flush);
+
+        // trips in the hand only play as a pair
+        let trips = [CLUB | ACE, DIAMOND | ACE, SPADE | ACE, CLUB | FOUR];
+        assert_eq!(omaha(&trips, &board).hand(), Hand::TwoPair);
+        assert_eq!(combinations(&trips, 2).len(), 6);
+
+        // three low cards in the hand still only use two of them
+        let low_board = [
+            HEART | TWO,
+            HEART | SEVEN,
+            HEART | EIGHT,
+            HEART | KING,
+            SPADE | KING,
+        ];
+        let low = [CLUB | ACE, CLUB | THREE, DIAMOND | FOUR, CLUB | KING];
+        assert_eq!(
+            omaha_eight_or_better(&low, &low_board),
+            eight_or_better(&[
+                CLUB | ACE,
+                CLUB | THREE,
+                HEART | TWO,
+                HEART | SEVEN,
+                HEART | EIGHT
+            ])
+        );
+        assert_eq!(omaha_eight_or_better(&low, &board), None);
+    }
+
+    #[test]
+    fn test_low_hands() {
+        let wheel = [
+            HEART | ACE,
+            CLUB | TWO,
+            HEART | THREE,
+            SPADE | FOUR,
+            HEART | FIVE,
+        ];
+        let six_four = [
+            HEART | SIX,
+            CLUB | FOUR,
+            HEART | THREE,
+            SPADE | TWO,
+            HEART | ACE,
+        ];
+        let pair = [
+            HEART | ACE,
+            CLUB | ACE,
+            HEART | THREE,
+            SPADE | FOUR,
+            HEART | FIVE,
+        ];
+        let king_high = [
+            HEART | KING,
+            CLUB | TWO,
+            HEART | THREE,
+            SPADE | FOUR,
+            HEART | FIVE,
+        ];
+
+        assert!(ace_to_five_low(&wheel) > ace_to_five_low(&six_four));
+        assert!(ace_to_five_low(&king_high) > ace_to_five_low(&pair));
+
+        // seven cards with a pair still make a six low
+        let seven = [
+            HEART | SIX,
+            CLUB | SIX,
+            HEART | THREE,
+            SPADE | TWO,
+            HEART | ACE,
+            DIAMOND | FOUR,
+            DIAMOND | KING,
+        ];
+        assert_eq!(ace_to_five_low(&seven), ace_to_five_low(&six_four));
+
+        assert!(eight_or_better(&king_high).is_none());
+        assert!(eight_or_better(&seven).is_some());
+    }
+
+    #[test]
+    fn test_deuce_to_seven() {
+        let number_one = [
+            HEART | SEVEN,
+            CLUB | FIVE,
+            HEART | FOUR,
+            SPADE | THREE,
+            HEART | TWO,
+        ];
+        let eight_low = [
+            HEART | EIGHT,
+            CLUB | FIVE,
+            HEART | FOUR,
+            SPADE | THREE,
+            HEART | TWO,
+        ];
+        let straight = [
+            HEART | SIX,
+            CLUB | FIVE,
+            HEART | FOUR,
+            SPADE | THREE,
+            HEART | TWO,
+        ];
+        let wheel = [
+            HEART | ACE,
+            CLUB | FIVE,
+            HEART | FOUR,
+            SPADE | THREE,
+            HEART | TWO,
+        ];
+        let pair = [
+            HEART | SEVEN,
+            CLUB | SEVEN,
+            HEART | FOUR,
+            SPADE | THREE,
+            HEART | TWO,
+        ];
+
+        assert!(deuce_to_seven_low(&number_one) > deuce_to_seven_low(&eight_low));
+        // straights count against the hand, the wheel is only ace high
+        assert!(deuce_to_seven_low(&eight_low) > deuce_to_seven_low(&straight));
+        assert!(deuce_to_seven_low(&wheel) > deuce_to_seven_low(&pair));
+        assert!(deuce_to_seven_low(&wheel) > deuce_to_seven_low(&straight));
+    }
+
+    #[test]
+    fn test_badugi() {
+        let badugi_hand = [HEART | FOUR, CLUB | TWO, SPADE | THREE, DIAMOND | KING];
+        let wheel_badugi = [HEART | ACE, CLUB | TWO, SPADE | THREE, DIAMOND | FOUR];
+        let three_card = [HEART | ACE, HEART | TWO, SPADE | THREE, DIAMOND | FOUR];
+
+        assert!(badugi(&wheel_badugi) > badugi(&badugi_hand));
+        assert!(badugi(&badugi_hand) > badugi(&three_card));
+    }
+}
@@ -1,45 +1,304 @@
-use crate::util::read_user_input;
+use std::{
+    future::{self, Future},
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
 
-use super::context::GameContext;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
+use crate::{
+    cards::rank,
+    get_suit, get_value,
+    util::{executor::Worker, forget_user_input, read_user_input},
+    Card, Hand, ACE, EIGHT,
+};
+
+use super::{
+    context::GameContext, hand::Event, profile::Profile, strategy::Strategy,
+    validation::IllegalActionPolicy, variant::Evaluator,
+};
+
+/// Decisions of a player.
+///
+/// Amounts are "to" amounts: the chips the player has in front of them for the
+/// current betting round after the action, including what they already put in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PlayerAction {
+    Check,
     Call,
-    Raise(usize),
+    /// Opens the betting round
+    Bet(usize),
+    /// Raises an existing bet, the blinds count as a bet
+    RaiseTo(usize),
+    /// Puts in every chip left, calling or raising depending on the stack
+    AllIn,
     Fold,
 }
 
-pub trait Actionable {
-    fn action(&self, game: &GameContext) -> PlayerAction {
+impl PlayerAction {
+    /// Bet or raise so the player has `to` in front of them
+    pub fn bet_or_raise_to(to: usize, current_bet: usize) -> Self {
+        if current_bet == 0 {
+            PlayerAction::Bet(to)
+        } else {
+            PlayerAction::RaiseTo(to)
+        }
+    }
+
+    /// Bet or raise by `amount` on top of the current bet
+    pub fn raise_by(amount: usize, current_bet: usize) -> Self {
+        Self::bet_or_raise_to(current_bet + amount, current_bet)
+    }
+
+    /// Chips the player has in front of them after the action, given what they
+    /// already put in this round and the chips left behind
+    pub fn target(&self, bet: usize, chips: usize, current_bet: usize) -> usize {
+        let stack = bet + chips;
+
+        match *self {
+            PlayerAction::Check | PlayerAction::Fold => bet,
+            PlayerAction::Call => current_bet.max(bet).min(stack),
+            PlayerAction::Bet(to) | PlayerAction::RaiseTo(to) => to.max(bet).min(stack),
+            PlayerAction::AllIn => stack,
+        }
+    }
+
+    /// Chips the action moves from the player's stack into the pot
+    pub fn chips_added(&self, bet: usize, chips: usize, current_bet: usize) -> usize {
+        self.target(bet, chips, current_bet) - bet
+    }
+}
+
+/// Cards a player throws away in a draw, one bit for every position in their hand
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Discard(pub u32);
+
+impl Discard {
+    /// Keeps every card
+    pub fn stand_pat() -> Self {
+        Self(0)
+    }
+
+    /// Discards the cards at `positions` of the hand, counting from zero
+    pub fn positions(positions: &[usize]) -> Self {
+        Self(positions.iter().fold(0, |acc, &p| acc | 1 << p))
+    }
+
+    pub fn contains(&self, position: usize) -> bool {
+        self.0 & (1 << position) != 0
+    }
+
+    pub fn count(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+}
+
+/// Decides for a player by blocking, the table runs the decisions on a thread of the player through `SyncActor`.
+pub trait Actionable: Send + Sync {
+    fn action(&self, _game: &GameContext) -> PlayerAction {
         PlayerAction::Fold
     }
 
     fn name(&self) -> &str {
         "Player"
     }
+
+    /// Cards to throw away when the game has a draw or a discard.
+    /// `GameContext::draw` says how many can be replaced, `GameContext::discard` how many have to go
+    fn discard(&self, _game: &GameContext) -> Discard {
+        Discard::stand_pat()
+    }
+
+    /// How the table handles an illegal action from this actor
+    fn illegal_action_policy(&self) -> IllegalActionPolicy {
+        IllegalActionPolicy::Fold
+    }
+
+    /// Asked after busting, buys back in for `buy_in` chips
+    fn wants_rebuy(&self, _buy_in: usize) -> bool {
+        false
+    }
+
+    /// Asked on the button in dealer's choice, picks the index of the next game out of `games`
+    fn choose_game(&self, _games: &[String]) -> usize {
+        0
+    }
+
+    /// Told about everything at the table this player gets to see, as it happens.
+    /// The table waits for it, so it should return quickly
+    fn notify(&self, _event: &Event) {}
+}
+
+/// A decision that is still being made, `None` if the actor crashed while making it
+pub type Decision<'a, T> = Pin<Box<dyn Future<Output = Option<T>> + Send + 'a>>;
+
+/// Decides for a player without blocking the table while waiting, for actors that
+/// get their decisions from somewhere else like a network client, a GUI or another process.
+/// The table waits for the decision until the time limit runs out.
+pub trait AsyncActionable: Send + Sync {
+    fn action(&self, game: GameContext) -> Decision<'_, PlayerAction>;
+
+    fn name(&self) -> &str {
+        "Player"
+    }
+
+    /// Cards to throw away when the game has a draw or a discard, see `Actionable::discard`
+    fn discard(&self, _game: GameContext) -> Decision<'_, Discard> {
+        Box::pin(future::ready(Some(Discard::stand_pat())))
+    }
+
+    fn illegal_action_policy(&self) -> IllegalActionPolicy {
+        IllegalActionPolicy::Fold
+    }
+
+    fn wants_rebuy(&self, _buy_in: usize) -> Decision<'_, bool> {
+        Box::pin(future::ready(Some(false)))
+    }
+
+    fn choose_game(&self, _games: Vec<String>) -> Decision<'_, usize> {
+        Box::pin(future::ready(Some(0)))
+    }
+
+    /// See `Actionable::notify`
+    fn notify(&self, _event: &Event) {}
+}
+
+/// Runs a blocking `Actionable` as an `AsyncActionable`, the decisions are made one after the
+/// other on a thread of the actor. A decision the table stops waiting for is cancelled
+pub struct SyncActor {
+    actor: Arc<dyn Actionable>,
+    /// Decisions are made right away on the thread asking for them without one
+    worker: Option<Worker>,
+}
+
+impl SyncActor {
+    pub fn new(actor: Arc<dyn Actionable>) -> Self {
+        Self {
+            actor,
+            worker: Some(Worker::new()),
+        }
+    }
+
+    /// Decides on the table's own thread, for tables without a time limit that would need another one
+    pub fn inline(actor: Arc<dyn Actionable>) -> Self {
+        Self {
+            actor,
+            worker: None,
+        }
+    }
+
+    /// Makes `decision` on the actor's thread
+    fn decide<T: Send + 'static>(
+        &self,
+        decision: impl FnOnce(&dyn Actionable) -> T + Send + 'static,
+    ) -> Decision<'_, T> {
+        let actor = Arc::clone(&self.actor);
+        match &self.worker {
+            Some(worker) => Box::pin(worker.run(move || decision(actor.as_ref()))),
+            None => {
+                let decided = panic::catch_unwind(AssertUnwindSafe(|| decision(actor.as_ref())));
+                Box::pin(future::ready(decided.ok()))
+            }
+        }
+    }
+}
+
+impl From<Box<dyn Actionable>> for SyncActor {
+    fn from(actor: Box<dyn Actionable>) -> Self {
+        Self::new(Arc::from(actor))
+    }
+}
+
+impl AsyncActionable for SyncActor {
+    fn action(&self, game: GameContext) -> Decision<'_, PlayerAction> {
+        self.decide(move |actor| actor.action(&game))
+    }
+
+    fn name(&self) -> &str {
+        self.actor.name()
+    }
+
+    fn discard(&self, game: GameContext) -> Decision<'_, Discard> {
+        self.decide(move |actor| actor.discard(&game))
+    }
+
+    fn illegal_action_policy(&self) -> IllegalActionPolicy {
+        self.actor.illegal_action_policy()
+    }
+
+    fn wants_rebuy(&self, buy_in: usize) -> Decision<'_, bool> {
+        self.decide(move |actor| actor.wants_rebuy(buy_in))
+    }
+
+    fn choose_game(&self, games: Vec<String>) -> Decision<'_, usize> {
+        self.decide(move |actor| actor.choose_game(&games))
+    }
+
+    fn notify(&self, event: &Event) {
+        self.actor.notify(event)
+    }
 }
 
 pub struct LocalPlayer;
 impl Actionable for LocalPlayer {
     fn action(&self, game: &GameContext) -> PlayerAction {
+        forget_user_input();
         println!("Your turn");
         println!("{} in pot, your cards: {}", game.pot, game.hand);
-        println!("Community cards: {}", game.community);
+        for (board, cards) in game.boards.iter().enumerate() {
+            match game.boards.len() {
+                1 => println!("Community cards: {}", cards),
+                _ => println!("Board {}: {}", board + 1, cards),
+            }
+        }
+
+        for (seat, (player, stats)) in game.players.iter().zip(&game.stats).enumerate() {
+            if let (false, false, Some(stats)) = (seat == game.seat, player.folded, stats) {
+                println!("{}: {}", player.name, stats);
+            }
+        }
 
         if game.contribution == game.current_bet {
             println!("1. Check");
         } else {
             println!("1. Call ({})", game.call_amount);
         }
-        println!("2. Raise ({} minimum)", game.min_raise);
+        if game.current_bet == 0 {
+            println!("2. Bet ({} minimum)", game.min_raise);
+        } else {
+            println!("2. Raise to ({} minimum)", game.min_raise);
+        }
         println!("3. Fold");
+        println!("4. All in ({})", game.contribution + game.chips);
+        if let Some(pot_raise) = game.pot_raise {
+            println!("5. Pot ({})", pot_raise);
+        }
 
-        match read_user_input() {
+        // the table gave up on the question or there is nobody left to answer it
+        let Some(choice) = read_user_input() else {
+            return PlayerAction::Fold;
+        };
+
+        match choice {
+            1 if game.contribution == game.current_bet => PlayerAction::Check,
             1 => PlayerAction::Call,
             2 => {
-                println!("Enter raise amount");
-                PlayerAction::Raise(read_user_input())
+                println!("Enter the total amount");
+                match read_user_input() {
+                    Some(to) => PlayerAction::bet_or_raise_to(to, game.current_bet),
+                    None => PlayerAction::Fold,
+                }
             }
             3 => PlayerAction::Fold,
+            4 => PlayerAction::AllIn,
+            5 => match game.pot_raise {
+                Some(to) => PlayerAction::bet_or_raise_to(to, game.current_bet),
+                None => {
+                    println!("Invalid choice");
+                    self.action(game)
+                }
+            },
             _ => {
                 println!("Invalid choice");
                 self.action(game)
@@ -47,31 +306,358 @@ impl Actionable for LocalPlayer {
         }
     }
 
+    fn discard(&self, game: &GameContext) -> Discard {
+        forget_user_input();
+        println!("Your cards:");
+        for (position, card) in game.hand.cards.iter().enumerate() {
+            println!("{}. {}", position + 1, crate::display_card(*card));
+        }
+        match game.discard {
+            Some(count) => println!("Enter the {} cards to discard one at a time", count),
+            None => println!(
+                "Enter the cards to discard one at a time, up to {}, 0 when done",
+                game.draw.unwrap_or(0)
+            ),
+        }
+
+        let mut positions = vec![];
+        while Some(positions.len()) != game.discard {
+            match read_user_input() {
+                None | Some(0) => break,
+                Some(position) if position <= game.hand.cards.len() => positions.push(position - 1),
+                _ => println!("Invalid choice"),
+            }
+        }
+
+        Discard::positions(&positions)
+    }
+
     fn name(&self) -> &str {
         "You"
     }
+
+    fn illegal_action_policy(&self) -> IllegalActionPolicy {
+        IllegalActionPolicy::Reprompt
+    }
+
+    fn wants_rebuy(&self, buy_in: usize) -> bool {
+        forget_user_input();
+        println!("You are out of chips, rebuy for {}?", buy_in);
+        println!("1. Yes");
+        println!("2. No");
+
+        read_user_input() == Some(1)
+    }
+
+    fn choose_game(&self, games: &[String]) -> usize {
+        forget_user_input();
+        println!("Dealer's choice, pick the next game");
+        for (i, game) in games.iter().enumerate() {
+            println!("{}. {}", i + 1, game);
+        }
+
+        read_user_input().map_or(0, |choice| choice.saturating_sub(1))
+    }
 }
 
+/// Hands a bot plays tilted after a lost showdown
+const TILT_HANDS: usize = 10;
+
+/// What a bot remembers of the hands it played
+#[derive(Debug, Default)]
+struct Mood {
+    seat: Option<usize>,
+    showed: bool,
+    won: bool,
+    /// Hands left to play tilted
+    tilted: usize,
+}
+
+/// Rule based bot that plays by hand strength and pot odds
 pub struct NPC {
     name: String,
+    strategy: Strategy,
+    /// Bluffs and the dealer's choice are random, seeding it makes them repeatable
+    rng: Mutex<StdRng>,
+    mood: Mutex<Mood>,
 }
 
 impl NPC {
     pub fn new(name: String) -> Self {
-        Self { name }
+        Self {
+            name,
+            strategy: Strategy::default(),
+            rng: Mutex::new(StdRng::from_entropy()),
+            mood: Mutex::new(Mood::default()),
+        }
+    }
+
+    /// A bot playing the way the profile describes
+    pub fn from_profile(profile: &Profile) -> Self {
+        Self::new(profile.name.clone()).with_strategy(profile.strategy())
+    }
+
+    pub fn with_strategy(self, strategy: Strategy) -> Self {
+        Self { strategy, ..self }
+    }
+
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self {
+            rng: Mutex::new(StdRng::seed_from_u64(seed)),
+            ..self
+        }
+    }
+
+    /// Whether a lost showdown put the bot on tilt
+    pub fn is_tilted(&self) -> bool {
+        self.mood.lock().unwrap().tilted > 0
     }
 }
 
 impl Actionable for NPC {
     fn action(&self, game: &GameContext) -> PlayerAction {
-        if game.contribution == 0 || game.contribution == game.current_bet {
-            return PlayerAction::Call;
-        }
+        let strategy = match self.is_tilted() {
+            true => self.strategy.tilted(),
+            false => self.strategy.clone(),
+        };
+        strategy.action(game, &mut *self.rng.lock().unwrap())
+    }
 
-        PlayerAction::Fold
+    fn discard(&self, game: &GameContext) -> Discard {
+        heuristic_discard(game)
     }
 
     fn name(&self) -> &str {
         self.name.as_str()
     }
+
+    fn illegal_action_policy(&self) -> IllegalActionPolicy {
+        IllegalActionPolicy::Call
+    }
+
+    fn choose_game(&self, games: &[String]) -> usize {
+        match games.len() {
+            0 => 0,
+            len => self.rng.lock().unwrap().gen_range(0..len),
+        }
+    }
+
+    fn notify(&self, event: &Event) {
+        let mut mood = self.mood.lock().unwrap();
+        match event {
+            Event::HandStarted { players, .. } => {
+                let lost = mood.showed && !mood.won;
+                mood.tilted = match lost && self.rng.lock().unwrap().gen_bool(self.strategy.tilt) {
+                    true => TILT_HANDS,
+                    false => mood.tilted.saturating_sub(1),
+                };
+                *mood = Mood {
+                    seat: players.iter().position(|name| *name == self.name),
+                    tilted: mood.tilted,
+                    ..Mood::default()
+                };
+            }
+            Event::Shown { seat, .. } if Some(*seat) == mood.seat => mood.showed = true,
+            Event::PotWon { seat, .. } if Some(*seat) == mood.seat => mood.won = true,
+            _ => {}
+        }
+    }
+}
+
+/// Keeps made hands and pairs, throws away the lowest unpaired cards.
+/// In lowball it draws to the cards up to an eight and in badugi to the lowest badugi instead
+pub fn heuristic_discard(game: &GameContext) -> Discard {
+    let cards = &game.hand.cards;
+    match game.evaluator {
+        Evaluator::DeuceToSeven => return lowball_discard(cards, game.draw.unwrap_or(0)),
+        Evaluator::Badugi => return badugi_discard(cards, game.draw.unwrap_or(0)),
+        _ => {}
+    }
+
+    if game.draw.is_some() && rank::high(cards).hand() >= Hand::Straight {
+        return Discard::stand_pat();
+    }
+
+    let paired = |i: usize| {
+        cards
+            .iter()
+            .filter(|&&c| get_value(c) == get_value(cards[i]))
+            .count()
+            > 1
+    };
+
+    // the first cards to throw away come first
+    let mut order: Vec<usize> = (0..cards.len()).collect();
+    order.sort_by_key(|&i| (paired(i), get_value(cards[i])));
+
+    if let Some(count) = game.discard {
+        order.truncate(count);
+        return Discard::positions(&order);
+    }
+
+    let mut singles: Vec<usize> = order.into_iter().filter(|&i| !paired(i)).collect();
+
+    // without a pair the highest card is worth keeping
+    if singles.len() == cards.len() {
+        singles.pop();
+    }
+
+    singles.truncate(game.draw.unwrap_or(0));
+    Discard::positions(&singles)
+}
+
+/// Throws away paired cards and everything above an eight, the highest first.
+/// Aces play high
+fn lowball_discard(cards: &[Card], limit: usize) -> Discard {
+    let mut kept = 0;
+    let mut thrown = vec![];
+
+    for (position, &card) in cards.iter().enumerate() {
+        let value = get_value(card);
+        if value > EIGHT || kept & value != 0 {
+            thrown.push(position);
+        } else {
+            kept |= value;
+        }
+    }
+
+    thrown.sort_by_key(|&i| std::cmp::Reverse(get_value(cards[i])));
+    thrown.truncate(limit);
+    Discard::positions(&thrown)
+}
+
+/// Keeps the lowest card of every suit as long as its value isn't kept already.
+/// Aces play low
+fn badugi_discard(cards: &[Card], limit: usize) -> Discard {
+    let low = |card: Card| match get_value(card) {
+        ACE => 1,
+        value => value,
+    };
+
+    let mut order: Vec<usize> = (0..cards.len()).collect();
+    order.sort_by_key(|&i| low(cards[i]));
+
+    let (mut suits, mut values) = (0, 0);
+    let mut thrown = vec![];
+    for position in order {
+        let card = cards[position];
+        if suits & get_suit(card) != 0 || values & get_value(card) != 0 {
+            thrown.push(position);
+        } else {
+            suits |= get_suit(card);
+            values |= get_value(card);
+        }
+    }
+
+    thrown.truncate(limit);
+    Discard::positions(&thrown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::strategy::HandClass;
+    use crate::*;
+
+    #[test]
+    fn test_bet_or_raise_to() {
+        assert_eq!(
+            PlayerAction::bet_or_raise_to(3000, 0),
+            PlayerAction::Bet(3000)
+        );
+        assert_eq!(
+            PlayerAction::bet_or_raise_to(6000, 2000),
+            PlayerAction::RaiseTo(6000)
+        );
+        assert_eq!(
+            PlayerAction::raise_by(4000, 2000),
+            PlayerAction::RaiseTo(6000)
+        );
+    }
+
+    #[test]
+    fn test_lost_showdown_tilts() {
+        let strategy = Strategy {
+            tilt: 1.0,
+            ..Strategy::default()
+        };
+        let bot = NPC::new("Bob".to_string()).with_strategy(strategy);
+        let started = Event::HandStarted {
+            game: "Texas Hold'em".to_string(),
+            button: 0,
+            players: vec!["Alice".to_string(), "Bob".to_string()],
+            dealt_in: vec![true; 2],
+        };
+
+        bot.notify(&started);
+        bot.notify(&Event::Shown {
+            seat: 1,
+            cards: CardStack::new(2),
+        });
+        bot.notify(&Event::PotWon {
+            seat: 0,
+            amount: 4000,
+            hand: Some(CardStack::new(2)),
+        });
+        assert!(!bot.is_tilted());
+
+        bot.notify(&started);
+        assert!(bot.is_tilted());
+        assert_eq!(
+            Strategy::default().tilted().opening[0],
+            HandClass::Speculative
+        );
+    }
+
+    #[test]
+    fn test_discard_positions() {
+        let discard = Discard::positions(&[0, 3]);
+
+        assert!(discard.contains(0));
+        assert!(!discard.contains(1));
+        assert!(discard.contains(3));
+        assert_eq!(discard.count(), 2);
+        assert_eq!(Discard::stand_pat().count(), 0);
+    }
+
+    #[test]
+    fn test_lowball_discard() {
+        let cards = [
+            HEART | ACE,
+            HEART | SEVEN,
+            CLUB | SEVEN,
+            SPADE | THREE,
+            HEART | TWO,
+        ];
+
+        assert_eq!(lowball_discard(&cards, 5), Discard::positions(&[0, 2]));
+        assert_eq!(lowball_discard(&cards, 1), Discard::positions(&[0]));
+    }
+
+    #[test]
+    fn test_badugi_discard() {
+        // the ace of hearts beats the two of hearts, the second three goes too
+        let cards = [HEART | TWO, CLUB | THREE, HEART | ACE, SPADE | THREE];
+
+        assert_eq!(badugi_discard(&cards, 4), Discard::positions(&[0, 3]));
+        assert_eq!(
+            badugi_discard(&[HEART | ACE, CLUB | TWO, SPADE | THREE, DIAMOND | FOUR], 4),
+            Discard::stand_pat()
+        );
+    }
+
+    #[test]
+    fn test_chips_added() {
+        // big blind of 2000 facing a raise to 6000 with 18000 behind
+        assert_eq!(PlayerAction::Call.chips_added(2000, 18000, 6000), 4000);
+        assert_eq!(
+            PlayerAction::RaiseTo(12000).chips_added(2000, 18000, 6000),
+            10000
+        );
+        assert_eq!(PlayerAction::AllIn.chips_added(2000, 18000, 6000), 18000);
+        assert_eq!(PlayerAction::Fold.chips_added(2000, 18000, 6000), 0);
+
+        // calls for more than the stack are all in
+        assert_eq!(PlayerAction::Call.chips_added(0, 1500, 6000), 1500);
+    }
 }
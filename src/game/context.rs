@@ -1,34 +1,278 @@
 use crate::{CardStack, CONCEALED};
 
-use super::player::Player;
+use super::{
+    actor::PlayerAction,
+    hand::{legal_actions, Action, Event, HandState},
+    player::Player,
+    seats::Positions,
+    stats::{Hud, PlayerStats},
+    variant::{Evaluator, Street},
+};
 
+/// What everybody at the table gets to see of a player
+#[derive(Debug, Clone)]
 pub struct GameContextPlayer {
-    // get name from actor somehow
+    pub name: String,
     pub chips: usize,
+    /// Chips in front of the player in the current betting round
+    pub bet: usize,
+    /// Chips the player put into the pot this hand
+    pub total_bet: usize,
+    /// Cards the player holds, `CONCEALED` unless they were dealt face up
     pub hand: CardStack,
+    /// Out of the hand, or not dealt in at all
+    pub folded: bool,
+    pub sitting_out: bool,
     pub eliminated: bool,
+    /// Cards drawn in every draw of the hand so far
+    pub draws: Vec<usize>,
 }
 
-impl Into<GameContextPlayer> for Player {
-    fn into(self) -> GameContextPlayer {
+impl GameContextPlayer {
+    /// Public view of `player`, the hole cards are never copied
+    pub fn new(player: &Player) -> Self {
         GameContextPlayer {
-            chips: self.chips,
-            hand: CardStack::from(vec![CONCEALED, CONCEALED]),
-            eliminated: self.eliminated,
+            name: player.name.clone(),
+            chips: player.chips,
+            bet: player.bet,
+            total_bet: player.total_bet,
+            // other players only get to see the cards dealt face up
+            hand: CardStack::from(
+                player
+                    .hand
+                    .cards
+                    .iter()
+                    .map(|card| match player.face_up.cards.contains(card) {
+                        true => *card,
+                        false => CONCEALED,
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            folded: player.folded,
+            sitting_out: player.sitting_out,
+            eliminated: player.eliminated,
+            draws: player.draws.clone(),
         }
     }
+
+    /// Whether the player kept all their cards in the last draw
+    pub fn stood_pat(&self) -> bool {
+        self.draws.last() == Some(&0)
+    }
+
+    /// Still in the hand without any chips left to bet
+    pub fn is_all_in(&self) -> bool {
+        !self.folded && self.chips == 0
+    }
 }
 
+/// Everything the player on `seat` gets to see of the hand, built from public state
+/// and their own cards only
+#[derive(Debug, Clone)]
 pub struct GameContext {
+    /// Seat of the player the context is for
+    pub seat: usize,
+    pub positions: Positions,
+    /// Name of the game being played
+    pub game: String,
+    /// How hands are ranked at showdown
+    pub evaluator: Evaluator,
+    pub street: &'static str,
     pub pot: usize,
+    /// Highest bet of the current betting round
     pub current_bet: usize,
     pub call_amount: usize,
+    /// Smallest amount a bet or raise has to go to, unless it is an all in
     pub min_raise: usize,
+    /// Most a bet or raise can go to, `None` when the player can't raise
+    pub max_raise: Option<usize>,
+    /// What a pot sized bet or raise goes to, `None` when the player can't raise
+    pub pot_raise: Option<usize>,
+    /// What the player can do right now, bets and raises at their minimum.
+    /// Empty when it isn't their turn
+    pub legal_actions: Vec<PlayerAction>,
+    /// Most cards the player can discard, only set while the hand waits for their discard
+    pub draw: Option<usize>,
+    /// Cards the player has to throw away without replacement, only set while the hand waits for it
+    pub discard: Option<usize>,
+    /// Pots after the main pot, opened by players all in for less, see `HandState::pots`
     pub side_pots: Vec<usize>,
-    pub community: CardStack,
+    /// Community cards, one stack per board
+    pub boards: Vec<CardStack>,
+    /// Community cards still to be dealt to every board
+    pub community_to_come: usize,
+    /// Hole cards still to be dealt to every player
+    pub hole_to_come: usize,
+    /// The player's own cards
     pub hand: CardStack,
     pub chips: usize,
+    /// Chips the player put in this betting round
     pub contribution: usize,
+    /// Chips behind that can still be won or lost, capped by the biggest other stack in the hand
+    pub effective_stack: usize,
+    /// Every player at the table by seat, the player's own hole cards are concealed here too
     pub players: Vec<GameContextPlayer>,
     pub player_contributions: Vec<usize>,
+    /// Everything that happened in the hand so far that the player got to see
+    pub history: Vec<Event>,
+    /// HUD stats of the players by seat, `None` for players without any
+    pub stats: Vec<Option<PlayerStats>>,
+}
+
+impl GameContext {
+    /// What the player on `seat` gets to see of the hand
+    pub fn new(state: &HandState, seat: usize) -> Self {
+        let player = &state.players[seat];
+        let current_bet = state.current_bet();
+        let raise_bounds = state.raise_bounds(seat);
+
+        let biggest_other_stack = state
+            .players
+            .iter()
+            .enumerate()
+            .filter(|&(other, p)| other != seat && !p.folded)
+            .map(|(_, p)| p.chips)
+            .max()
+            .unwrap_or(0);
+
+        let (hole_to_come, community_to_come) = state
+            .streets
+            .iter()
+            .skip(state.street + 1)
+            .map(Street::card_counts)
+            .fold((0, 0), |(hole, community), (h, c)| {
+                (hole + h, community + c)
+            });
+
+        GameContext {
+            seat,
+            positions: state.positions,
+            game: state.variant.name(),
+            evaluator: state.variant.evaluator(),
+            street: state.street_name(),
+            pot: state.pot(),
+            current_bet,
+            call_amount: state.call_amount(seat),
+            min_raise: current_bet + state.min_raise,
+            max_raise: raise_bounds.map(|(_, maximum)| maximum),
+            // capped by the betting structure and the stack
+            pot_raise: raise_bounds.map(|(_, maximum)| {
+                maximum.min(current_bet + state.pot() + state.call_amount(seat))
+            }),
+            legal_actions: legal_actions(state)
+                .into_iter()
+                .filter_map(|action| match action {
+                    Action::Act(actor, action) if actor == seat => Some(action),
+                    _ => None,
+                })
+                .collect(),
+            draw: (state.to_draw == Some(seat) && state.replace_discards)
+                .then_some(state.draw_limit),
+            discard: (state.to_draw == Some(seat) && !state.replace_discards)
+                .then_some(state.draw_limit),
+            contribution: player.bet,
+            side_pots: state.pots().split_off(1),
+            boards: state.boards.clone(),
+            community_to_come,
+            hole_to_come,
+            hand: player.hand.clone(),
+            chips: player.chips,
+            effective_stack: player.chips.min(biggest_other_stack),
+            players: state.players.iter().map(GameContextPlayer::new).collect(),
+            player_contributions: state.players.iter().map(|p| p.bet).collect(),
+            history: state
+                .history
+                .iter()
+                .filter(|event| event.is_visible_to(seat))
+                .cloned()
+                .collect(),
+            stats: vec![None; state.players.len()],
+        }
+    }
+
+    /// Adds the stats the HUD kept of the players
+    pub fn with_stats(self, hud: &Hud) -> Self {
+        Self {
+            stats: self.players.iter().map(|p| hud.stats(&p.name)).collect(),
+            ..self
+        }
+    }
+
+    /// Stack to pot ratio, `None` while the pot is empty
+    pub fn spr(&self) -> Option<f64> {
+        (self.pot > 0).then(|| self.effective_stack as f64 / self.pot as f64)
+    }
+
+    pub fn is_button(&self) -> bool {
+        self.positions.button == self.seat
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::game::{
+        hand::{apply, new_game},
+        variants::Holdem,
+    };
+
+    #[test]
+    fn test_context_only_shows_public_state() {
+        let state = [Action::PostBlinds, Action::Deal].iter().fold(
+            new_game(Arc::new(Holdem::default()), &[20000, 20000, 5000]),
+            |state, &action| apply(&state, action).unwrap().0,
+        );
+
+        let context = GameContext::new(&state, 0);
+
+        assert_eq!(context.players[2].name, "Player 2");
+        assert!(context.is_button());
+        assert_eq!(context.street, "Preflop");
+        assert_eq!(context.community_to_come, 5);
+        assert_eq!(context.hole_to_come, 0);
+        assert_eq!(context.hand, state.players[0].hand);
+        assert!(context
+            .players
+            .iter()
+            .all(|p| p.hand.cards == vec![CONCEALED; 2]));
+
+        // only the player's own hole cards are in the history
+        let hole_cards: Vec<&Event> = context
+            .history
+            .iter()
+            .filter(|e| matches!(e, Event::HoleCards { .. }))
+            .collect();
+        assert_eq!(hole_cards.len(), 1);
+        assert!(matches!(hole_cards[0], Event::HoleCards { seat: 0, .. }));
+
+        assert_eq!(
+            context.legal_actions,
+            vec![
+                PlayerAction::Call,
+                PlayerAction::RaiseTo(4000),
+                PlayerAction::AllIn,
+                PlayerAction::Fold
+            ]
+        );
+        assert_eq!(context.max_raise, Some(20000));
+        assert!(GameContext::new(&state, 1).legal_actions.is_empty());
+
+        // the big blind has 3000 behind
+        assert_eq!(context.effective_stack, 19000);
+        assert_eq!(GameContext::new(&state, 2).effective_stack, 3000);
+        assert_eq!(GameContext::new(&state, 2).spr(), Some(1.0));
+        assert!(context.side_pots.is_empty());
+
+        // the short stack calling all in opens a side pot for the other two
+        let state = [
+            Action::Act(0, PlayerAction::AllIn),
+            Action::Act(1, PlayerAction::Call),
+            Action::Act(2, PlayerAction::Call),
+        ]
+        .iter()
+        .fold(state, |state, &action| apply(&state, action).unwrap().0);
+        assert_eq!(GameContext::new(&state, 0).side_pots, vec![30000]);
+    }
 }
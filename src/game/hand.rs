@@ -0,0 +1,2364 @@
+use std::sync::Arc;
+
+use crate::{cards::rank::Rank, Card, CardStack};
+
+use super::{
+    actor::{Discard, PlayerAction},
+    player::{OwedBlinds, Player},
+    seats::{next_seat, Positions},
+    validation::IllegalAction,
+    variant::{BettingStructure, Deal, Face, ForcedBets, HandValue, Street, Variant},
+};
+
+/// Fixed limit betting allows a bet and three raises per street
+pub const RAISE_CAP: usize = 4;
+
+/// The transition a hand waits for next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// The blinds or antes have to be posted
+    Blinds,
+    /// Cards for the current street have to be dealt
+    Deal,
+    /// The player in `HandState::to_act` has to act
+    Betting,
+    /// The player in `HandState::to_draw` has to discard
+    Draw,
+    /// The pots have to be awarded
+    Showdown,
+    /// The hand is over
+    Finished,
+}
+
+/// Steps that move a hand forward, either done by the dealer or by a player
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    PostBlinds,
+    Deal,
+    Act(usize, PlayerAction),
+    /// Discards of the player on the seat in a draw
+    Draw(usize, Discard),
+    /// The player on the seat ran out of time, they check if they can and fold otherwise.
+    /// In a draw they throw away `HandState::default_discard`
+    TimeOut(usize),
+    Showdown,
+}
+
+/// Everything that happened while applying an `Action`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// A new hand with the names of everybody at the table by seat, `dealt_in` says who plays it
+    HandStarted {
+        game: String,
+        button: usize,
+        players: Vec<String>,
+        dealt_in: Vec<bool>,
+    },
+    /// The cards of a street are out and its betting starts
+    StreetStarted {
+        street: &'static str,
+    },
+    Ante {
+        seat: usize,
+        amount: usize,
+    },
+    /// Forced bet of the weakest door card in stud games
+    BringIn {
+        seat: usize,
+        amount: usize,
+    },
+    SmallBlind {
+        seat: usize,
+        amount: usize,
+    },
+    DeadSmallBlind,
+    BigBlind {
+        seat: usize,
+        amount: usize,
+    },
+    /// Blinds posted by a player coming back or joining, `dead` chips don't count as a bet
+    OwedBlinds {
+        seat: usize,
+        live: usize,
+        dead: usize,
+    },
+    /// Face down hole cards, only meant for the player on `seat`
+    HoleCards {
+        seat: usize,
+        cards: CardStack,
+    },
+    /// Hole cards dealt face up, everybody sees them
+    UpCards {
+        seat: usize,
+        cards: CardStack,
+    },
+    /// New community cards for the street on `board`, counted from 0
+    Community {
+        street: &'static str,
+        board: usize,
+        cards: CardStack,
+    },
+    Checked {
+        seat: usize,
+    },
+    Called {
+        seat: usize,
+        amount: usize,
+    },
+    Bet {
+        seat: usize,
+        amount: usize,
+    },
+    Raised {
+        seat: usize,
+        to: usize,
+    },
+    Folded {
+        seat: usize,
+    },
+    AllIn {
+        seat: usize,
+    },
+    TimedOut {
+        seat: usize,
+    },
+    /// Number of cards the player on `seat` drew, everybody sees it
+    Drew {
+        seat: usize,
+        count: usize,
+    },
+    /// The player on `seat` threw away cards without replacement, only the count is public
+    Discarded {
+        seat: usize,
+        count: usize,
+    },
+    BettingClosed,
+    /// Cards shown down by a player that is still in the hand
+    Shown {
+        seat: usize,
+        cards: CardStack,
+    },
+    /// Chips from the pot, `hand` is the winning hand if there was a showdown
+    PotWon {
+        seat: usize,
+        amount: usize,
+        hand: Option<CardStack>,
+    },
+}
+
+impl Event {
+    /// Whether the player on `seat` gets to know about the event, hole cards are only for their owner
+    pub fn is_visible_to(&self, seat: usize) -> bool {
+        match self {
+            Event::HoleCards { seat: owner, .. } => *owner == seat,
+            _ => true,
+        }
+    }
+
+    /// Whether everybody at the table gets to know about the event
+    pub fn is_public(&self) -> bool {
+        !matches!(self, Event::HoleCards { .. })
+    }
+}
+
+/// State of a single hand, moved forward by `apply`
+#[derive(Debug, Clone)]
+pub struct HandState {
+    pub variant: Arc<dyn Variant>,
+    pub players: Vec<Player>,
+    pub positions: Positions,
+    pub blinds: usize,
+    /// Streets of the variant, in order
+    pub streets: Vec<Street>,
+    /// Index into `streets`, past the last one once the betting is over
+    pub street: usize,
+    pub phase: Phase,
+    pub deck: CardStack,
+    /// Community cards, one stack per board
+    pub boards: Vec<CardStack>,
+    pub to_act: Option<usize>,
+    /// Seat that has to discard while the hand is in a draw
+    pub to_draw: Option<usize>,
+    /// Most cards a player can discard in the current draw
+    pub draw_limit: usize,
+    /// Whether discards get replaced. Without replacement players discard exactly `draw_limit` cards
+    pub replace_discards: bool,
+    /// Discarded cards, shuffled back into the deck when it runs out
+    pub muck: CardStack,
+    /// Size of the last full raise, the next raise has to be at least as big
+    pub min_raise: usize,
+    /// Bets and raises so far in the current betting round
+    pub raises: usize,
+    /// Every event of the hand so far, hole cards of all players included
+    pub history: Vec<Event>,
+    acted: Vec<bool>,
+    /// Seat that acted last, action continues left of it
+    last_seat: usize,
+    /// Deals of the current street that are done
+    deal_index: usize,
+    /// Seats that still have to discard in the current draw
+    pending_draws: Vec<bool>,
+}
+
+impl HandState {
+    /// Starts a hand with everyone that is playing dealt in.
+    /// `deck` is dealt from the back and should be shuffled already.
+    pub fn new(
+        variant: Arc<dyn Variant>,
+        players: Vec<Player>,
+        positions: Positions,
+        blinds: usize,
+        deck: CardStack,
+    ) -> Self {
+        let streets = variant.streets();
+        let boards = variant.boards();
+        let (hole_cards, community_cards) = variant.card_counts();
+
+        let players: Vec<Player> = players
+            .into_iter()
+            .map(|mut player| {
+                player.hand = CardStack::new(hole_cards);
+                player.face_up = CardStack::new(0);
+                player.draws = vec![];
+                player.folded = !player.is_playing();
+                player.bet = 0;
+                player.total_bet = 0;
+                player
+            })
+            .collect();
+
+        let seats = players.len();
+        Self {
+            variant,
+            players,
+            positions,
+            blinds,
+            streets,
+            street: 0,
+            phase: Phase::Blinds,
+            deck,
+            boards: vec![CardStack::new(community_cards); boards],
+            to_act: None,
+            to_draw: None,
+            draw_limit: 0,
+            replace_discards: true,
+            muck: CardStack::new(0),
+            min_raise: blinds,
+            raises: 0,
+            history: vec![],
+            acted: vec![false; seats],
+            last_seat: positions.big_blind,
+            deal_index: 0,
+            pending_draws: vec![false; seats],
+        }
+    }
+
+    /// Sum of all bets
+    pub fn pot(&self) -> usize {
+        self.players.iter().map(|p| p.total_bet).sum()
+    }
+
+    /// The main pot and the side pots after it, split at the stacks of the players that are all in.
+    /// Chips above the last all in make up the last pot
+    pub fn pots(&self) -> Vec<usize> {
+        let mut levels: Vec<usize> = self
+            .players
+            .iter()
+            .filter(|p| !p.folded && p.chips == 0 && p.total_bet > 0)
+            .map(|p| p.total_bet)
+            .collect();
+        levels.sort();
+        levels.dedup();
+
+        let mut pots = vec![];
+        let mut previous = 0;
+        for level in levels {
+            pots.push(
+                self.players
+                    .iter()
+                    .map(|p| p.total_bet.clamp(previous, level) - previous)
+                    .sum(),
+            );
+            previous = level;
+        }
+
+        let rest: usize = self
+            .players
+            .iter()
+            .map(|p| p.total_bet.saturating_sub(previous))
+            .sum();
+        if rest > 0 || pots.is_empty() {
+            pots.push(rest);
+        }
+
+        pots
+    }
+
+    /// Highest amount a player put in this betting round
+    pub fn current_bet(&self) -> usize {
+        self.players.iter().map(|p| p.bet).max().unwrap_or(0)
+    }
+
+    /// Chips the player on `seat` needs to put in to call
+    pub fn call_amount(&self, seat: usize) -> usize {
+        self.current_bet() - self.players[seat].bet
+    }
+
+    /// Seats of players that can still act in the current hand
+    pub fn acting_seats(&self) -> Vec<bool> {
+        self.players
+            .iter()
+            .map(|p| !p.folded && p.is_playing() && p.chips > 0)
+            .collect()
+    }
+
+    /// Name of the current street
+    pub fn street_name(&self) -> &'static str {
+        self.streets
+            .get(self.street)
+            .map_or("Showdown", |street| street.name)
+    }
+
+    /// Size of a bet in fixed limit games, the big blind or twice that on big bet streets
+    pub fn bet_size(&self) -> usize {
+        match self.streets.get(self.street) {
+            Some(street) if street.big_bet => self.blinds * 2,
+            _ => self.blinds,
+        }
+    }
+
+    /// Smallest and biggest amount the player on `seat` can bet or raise to,
+    /// `None` if they can only call or fold
+    pub fn raise_bounds(&self, seat: usize) -> Option<(usize, usize)> {
+        let player = &self.players[seat];
+        let stack = player.bet + player.chips;
+        let current_bet = self.current_bet();
+
+        // raising against players that are all in makes no sense
+        let others_can_act = self
+            .acting_seats()
+            .iter()
+            .enumerate()
+            .any(|(other, &acting)| acting && other != seat);
+
+        // players that acted already only get to raise again after a full raise,
+        // an all in for less lets them call or fold
+        if stack <= current_bet || !others_can_act || self.acted[seat] {
+            return None;
+        }
+
+        let minimum = current_bet + self.min_raise;
+        let maximum = match self.variant.betting() {
+            BettingStructure::NoLimit => stack,
+            // a pot sized raise calls first and then raises by the whole pot
+            BettingStructure::PotLimit => current_bet + self.pot() + self.call_amount(seat),
+            BettingStructure::FixedLimit => {
+                // heads-up there is no cap
+                if self.raises >= RAISE_CAP && self.in_hand_count() > 2 {
+                    return None;
+                }
+                minimum
+            }
+        };
+
+        Some((minimum.min(stack), maximum.min(stack)))
+    }
+
+    fn in_hand_count(&self) -> usize {
+        self.players.iter().filter(|p| !p.folded).count()
+    }
+
+    fn put_in(&mut self, seat: usize, amount: usize) -> usize {
+        let player = &mut self.players[seat];
+        let amount = amount.min(player.chips);
+
+        player.chips -= amount;
+        player.bet += amount;
+        player.total_bet += amount;
+        amount
+    }
+
+    fn deal(&mut self) -> Result<Card, IllegalAction> {
+        self.deck.pop().ok_or(IllegalAction::DeckExhausted)
+    }
+
+    /// Deals a replacement card, the discards become the new deck once the deck runs out
+    fn draw_card(&mut self) -> Result<Card, IllegalAction> {
+        if self.deck.cards.is_empty() {
+            self.deck.cards.append(&mut self.muck.cards);
+            self.deck.shuffle();
+        }
+
+        self.deal()
+    }
+
+    /// Picks the next player to act or closes the betting round
+    fn advance(&mut self, events: &mut Vec<Event>) {
+        let acting = self.acting_seats();
+        let current_bet = self.current_bet();
+
+        let pending: Vec<usize> = (0..acting.len())
+            .filter(|&seat| acting[seat])
+            .filter(|&seat| !self.acted[seat] || self.players[seat].bet < current_bet)
+            .collect();
+
+        // a single player that can act has nobody to bet against once the bets are matched
+        let nobody_to_bet_against = acting.iter().filter(|&&a| a).count() == 1
+            && pending
+                .iter()
+                .all(|&seat| self.players[seat].bet == current_bet);
+
+        if self.in_hand_count() < 2 || pending.is_empty() || nobody_to_bet_against {
+            if self.phase == Phase::Betting {
+                events.push(Event::BettingClosed);
+            }
+
+            self.to_act = None;
+            self.phase = if self.in_hand_count() < 2 {
+                Phase::Showdown
+            } else {
+                self.street += 1;
+                match self.street < self.streets.len() {
+                    true => Phase::Deal,
+                    false => Phase::Showdown,
+                }
+            };
+            return;
+        }
+
+        let pending_seats: Vec<bool> = (0..acting.len())
+            .map(|seat| pending.contains(&seat))
+            .collect();
+        self.to_act = next_seat(&pending_seats, self.last_seat);
+        self.phase = Phase::Betting;
+    }
+
+    fn start_street(&mut self, events: &mut Vec<Event>) {
+        let first_street = self.street == 0;
+        if !first_street {
+            self.players.iter_mut().for_each(|p| p.bet = 0);
+        }
+
+        events.push(Event::StreetStarted {
+            street: self.street_name(),
+        });
+
+        self.acted = vec![false; self.players.len()];
+        self.min_raise = self.bet_size();
+        self.raises = 0;
+
+        self.last_seat = match (self.variant.forced_bets(), first_street) {
+            // on the first street the action starts left of the big blind, heads-up that is the button
+            (ForcedBets::Blinds, true) => {
+                // the big blind counts as the first bet
+                self.raises = 1;
+                self.positions.big_blind
+            }
+            (ForcedBets::Blinds, false) => self.positions.button,
+            (ForcedBets::BringIn { bring_in, .. }, true) => self.post_bring_in(bring_in, events),
+            (ForcedBets::BringIn { .. }, false) => {
+                let len = self.players.len();
+                (self.best_visible_hand() + len - 1) % len
+            }
+        };
+        self.advance(events);
+    }
+
+    /// Seats still in the hand, starting left of the button
+    fn seats_in_hand(&self) -> Vec<usize> {
+        let len = self.players.len();
+        (1..=len)
+            .map(|offset| (self.positions.button + offset) % len)
+            .filter(|&seat| !self.players[seat].folded)
+            .collect()
+    }
+
+    /// The player with the weakest door card posts the bring-in and the action goes on left of them
+    fn post_bring_in(&mut self, percent: usize, events: &mut Vec<Event>) -> usize {
+        let seat = self
+            .seats_in_hand()
+            .into_iter()
+            .min_by_key(|&seat| {
+                let door = self.players[seat].face_up.cards.first().copied();
+                door.map(|card| self.variant.bring_in_order(card))
+            })
+            .expect("Somebody has to bring it in");
+
+        let amount = self.put_in(seat, self.bet_size() * percent / 100);
+        events.push(Event::BringIn { seat, amount });
+
+        // completing the bring-in to a full bet is the first raise
+        self.min_raise = self.bet_size() - amount;
+        self.acted[seat] = true;
+        seat
+    }
+
+    /// Seat with the strongest face up cards, ties go to the first seat left of the button
+    fn best_visible_hand(&self) -> usize {
+        self.seats_in_hand()
+            .into_iter()
+            .rev()
+            .max_by_key(|&seat| self.variant.visible_rank(&self.players[seat].face_up.cards))
+            .expect("Somebody is still in the hand")
+    }
+
+    /// Posts the forced bets of the variant
+    fn post_forced_bets(&mut self, events: &mut Vec<Event>) {
+        events.push(Event::HandStarted {
+            game: self.variant.name(),
+            button: self.positions.button,
+            players: self.players.iter().map(|p| p.name.clone()).collect(),
+            dealt_in: self.players.iter().map(|p| !p.folded).collect(),
+        });
+
+        match self.variant.forced_bets() {
+            ForcedBets::Blinds => self.post_blinds(events),
+            ForcedBets::BringIn { ante, .. } => self.post_antes(ante, events),
+        }
+
+        self.phase = Phase::Deal;
+    }
+
+    /// Antes are dead money, they don't count towards the bets of the first street
+    fn post_antes(&mut self, percent: usize, events: &mut Vec<Event>) {
+        let ante = self.blinds * percent / 100;
+
+        for seat in 0..self.players.len() {
+            let player = &mut self.players[seat];
+            if !player.is_playing() {
+                continue;
+            }
+
+            // there are no blinds to owe without a button
+            player.owed_blinds = OwedBlinds::None;
+
+            let amount = ante.min(player.chips);
+            player.chips -= amount;
+            player.total_bet += amount;
+            events.push(Event::Ante { seat, amount });
+        }
+    }
+
+    fn post_blinds(&mut self, events: &mut Vec<Event>) {
+        let Positions {
+            small_blind,
+            big_blind,
+            ..
+        } = self.positions;
+
+        if !self.players[small_blind].is_playing() {
+            events.push(Event::DeadSmallBlind);
+        } else {
+            let amount = self.put_in(small_blind, self.blinds / 2);
+            events.push(Event::SmallBlind {
+                seat: small_blind,
+                amount,
+            });
+        }
+
+        let amount = self.put_in(big_blind, self.blinds);
+        events.push(Event::BigBlind {
+            seat: big_blind,
+            amount,
+        });
+
+        for seat in 0..self.players.len() {
+            let owed = self.players[seat].owed_blinds;
+            if owed == OwedBlinds::None || !self.players[seat].is_playing() {
+                continue;
+            }
+
+            // the blinds of this hand settle the debt
+            self.players[seat].owed_blinds = OwedBlinds::None;
+            if seat == small_blind || seat == big_blind {
+                continue;
+            }
+
+            let live = self.put_in(seat, self.blinds);
+            let dead = match owed {
+                OwedBlinds::Both => {
+                    let player = &mut self.players[seat];
+                    let dead = (self.blinds / 2).min(player.chips);
+                    player.chips -= dead;
+                    player.total_bet += dead;
+                    dead
+                }
+                _ => 0,
+            };
+
+            events.push(Event::OwedBlinds { seat, live, dead });
+        }
+    }
+
+    fn deal_street(&mut self, events: &mut Vec<Event>) -> Result<(), IllegalAction> {
+        let street = self.streets[self.street].clone();
+
+        // a draw stops the dealing until everybody discarded, then it picks up from here
+        while let Some(&deal) = street.deals.get(self.deal_index) {
+            self.deal_index += 1;
+
+            match deal {
+                Deal::Hole { count, .. }
+                    if self.deck.cards.len() < count * self.seats_in_hand().len() =>
+                {
+                    // not enough cards to go around, everybody shares a card on the board instead
+                    let mut cards = CardStack::new(count);
+                    for _ in 0..count {
+                        cards.push(self.deal()?);
+                    }
+
+                    for (board, community) in self.boards.iter_mut().enumerate() {
+                        cards.cards.iter().for_each(|&card| community.push(card));
+                        events.push(Event::Community {
+                            street: street.name,
+                            board,
+                            cards: cards.clone(),
+                        });
+                    }
+                }
+                Deal::Hole { count, face } => {
+                    let seats: Vec<usize> = (0..self.players.len())
+                        .filter(|&seat| !self.players[seat].folded)
+                        .collect();
+                    let mut dealt = vec![CardStack::new(count); self.players.len()];
+
+                    for _ in 0..count {
+                        for &seat in seats.iter() {
+                            let card = self.deal()?;
+                            self.players[seat].hand.push(card);
+                            if face == Face::Up {
+                                self.players[seat].face_up.push(card);
+                            }
+                            dealt[seat].push(card);
+                        }
+                    }
+
+                    for seat in seats {
+                        let cards = dealt[seat].clone();
+                        events.push(match face {
+                            Face::Down => Event::HoleCards { seat, cards },
+                            Face::Up => Event::UpCards { seat, cards },
+                        });
+                    }
+                }
+                Deal::Community { count } => {
+                    for board in 0..self.boards.len() {
+                        let mut cards = CardStack::new(count);
+                        for _ in 0..count {
+                            let card = self.deal()?;
+                            cards.push(card);
+                            self.boards[board].push(card);
+                        }
+
+                        events.push(Event::Community {
+                            street: street.name,
+                            board,
+                            cards,
+                        });
+                    }
+                }
+                Deal::Draw { max: count } | Deal::Discard { count } => {
+                    self.draw_limit = count;
+                    self.replace_discards = matches!(deal, Deal::Draw { .. });
+                    self.pending_draws = self.players.iter().map(|p| !p.folded).collect();
+                    self.to_draw = next_seat(&self.pending_draws, self.positions.button);
+                    self.phase = Phase::Draw;
+                    return Ok(());
+                }
+            }
+        }
+
+        self.deal_index = 0;
+        self.start_street(events);
+        Ok(())
+    }
+
+    /// What a player that doesn't decide discards: nothing in a draw, the first cards otherwise
+    pub fn default_discard(&self) -> Discard {
+        match self.replace_discards {
+            true => Discard::stand_pat(),
+            false => Discard::positions(&(0..self.draw_limit).collect::<Vec<_>>()),
+        }
+    }
+
+    /// Swaps the discarded cards for new ones and moves on to the next player to draw.
+    /// Cards thrown away without replacement are dead
+    fn draw(
+        &mut self,
+        seat: usize,
+        discard: Discard,
+        events: &mut Vec<Event>,
+    ) -> Result<(), IllegalAction> {
+        let hand = &self.players[seat].hand.cards;
+        let split = |discarded: bool| -> Vec<Card> {
+            (0..hand.len())
+                .filter(|&position| discard.contains(position) == discarded)
+                .map(|position| hand[position])
+                .collect()
+        };
+        let (discarded, kept) = (split(true), split(false));
+
+        let replacements = match self.replace_discards {
+            true => discarded.len(),
+            false => 0,
+        };
+        let mut cards = CardStack::new(replacements);
+        for _ in 0..replacements {
+            let card = self.draw_card()?;
+            cards.push(card);
+        }
+
+        // the discards only go to the muck now, so nobody draws their own cards back
+        discarded.iter().for_each(|&card| self.muck.push(card));
+
+        let player = &mut self.players[seat];
+        player.hand.cards = kept;
+        player.hand.cards.extend(cards.cards.iter());
+
+        if self.replace_discards {
+            player.draws.push(discarded.len());
+            events.push(Event::Drew {
+                seat,
+                count: discarded.len(),
+            });
+        } else {
+            events.push(Event::Discarded {
+                seat,
+                count: discarded.len(),
+            });
+        }
+
+        if !cards.cards.is_empty() {
+            events.push(Event::HoleCards { seat, cards });
+        }
+
+        self.pending_draws[seat] = false;
+        self.to_draw = next_seat(&self.pending_draws, seat);
+        if self.to_draw.is_none() {
+            self.phase = Phase::Deal;
+            self.deal_street(events)?;
+        }
+
+        Ok(())
+    }
+
+    fn act(&mut self, seat: usize, action: PlayerAction, events: &mut Vec<Event>) {
+        let current_bet = self.current_bet();
+        let call_amount = self.call_amount(seat);
+
+        self.acted[seat] = true;
+        self.last_seat = seat;
+
+        match action {
+            PlayerAction::Check => events.push(Event::Checked { seat }),
+            PlayerAction::Call if call_amount == 0 => events.push(Event::Checked { seat }),
+            PlayerAction::Call => {
+                let amount = self.put_in(seat, call_amount);
+                events.push(Event::Called { seat, amount });
+            }
+            PlayerAction::Bet(_) | PlayerAction::RaiseTo(_) | PlayerAction::AllIn => {
+                let player = &self.players[seat];
+                let to = action.target(player.bet, player.chips, current_bet);
+                let amount = self.put_in(seat, to - player.bet);
+
+                if to <= current_bet {
+                    // all in for no more than a call
+                    events.push(Event::Called { seat, amount });
+                } else {
+                    if current_bet == 0 {
+                        events.push(Event::Bet { seat, amount });
+                    } else {
+                        events.push(Event::Raised { seat, to });
+                    }
+
+                    // an all in for less than a full raise doesn't reopen the betting for the players
+                    // that acted already and doesn't count towards the cap of limit games
+                    if to - current_bet >= self.min_raise {
+                        self.raises += 1;
+                        self.acted.iter_mut().for_each(|a| *a = false);
+                        self.acted[seat] = true;
+                    }
+
+                    // an all in for less than a full raise keeps the minimum as it is,
+                    // limit games always raise by a full bet
+                    self.min_raise = match self.variant.betting() {
+                        BettingStructure::FixedLimit => self.bet_size(),
+                        _ => self.min_raise.max(to - current_bet),
+                    };
+                }
+            }
+            PlayerAction::Fold => {
+                self.players[seat].folded = true;
+                events.push(Event::Folded { seat });
+            }
+        }
+
+        if !self.players[seat].folded && self.players[seat].chips == 0 {
+            events.push(Event::AllIn { seat });
+        }
+
+        self.advance(events);
+    }
+
+    /// Splits the pot and all side pots between the best hands.
+    /// With several boards each pot is shared equally between the boards,
+    /// in split games every share is halved between the best high and the best low.
+    fn showdown(&mut self, events: &mut Vec<Event>) {
+        let bets: Vec<usize> = self.players.iter().map(|p| p.total_bet).collect();
+        let contested = self.in_hand_count() > 1;
+
+        let mut levels: Vec<usize> = self
+            .players
+            .iter()
+            .filter(|p| !p.folded)
+            .map(|p| p.total_bet)
+            .collect();
+        levels.sort();
+        levels.dedup();
+
+        // what every seat is worth on each board
+        let values: Vec<Vec<HandValue>> = self
+            .boards
+            .iter()
+            .map(|board| {
+                self.players
+                    .iter()
+                    .map(|p| self.variant.evaluate(&p.hand.cards, &board.cards))
+                    .collect()
+            })
+            .collect();
+
+        if contested {
+            for (seat, player) in self.players.iter().enumerate() {
+                if !player.folded {
+                    events.push(Event::Shown {
+                        seat,
+                        cards: player.hand.clone(),
+                    });
+                }
+            }
+        }
+
+        let mut previous_level = 0;
+        for (level_idx, &level) in levels.iter().enumerate() {
+            // uncalled chips above the last level go back to whoever bet them
+            let is_last = level_idx == levels.len() - 1;
+            let pot: usize = bets
+                .iter()
+                .map(|&bet| {
+                    let upper = if is_last { bet } else { bet.min(level) };
+                    upper - bet.min(previous_level)
+                })
+                .sum();
+            previous_level = level;
+
+            let contenders: Vec<usize> = (0..self.players.len())
+                .filter(|&seat| !self.players[seat].folded && bets[seat] >= level)
+                .collect();
+
+            // the odd chips of a pot split between boards go to the first board
+            let boards = values.len();
+            for (board, values) in values.iter().enumerate() {
+                let pot = pot / boards + if board == 0 { pot % boards } else { 0 };
+                let highs: Vec<_> = values.iter().map(|v| Some(v.rank)).collect();
+                let lows: Vec<_> = values.iter().map(|v| v.low).collect();
+
+                // the odd chip of a split pot goes to the high hand
+                let low_pot = match contenders.iter().any(|&seat| lows[seat].is_some()) {
+                    true => pot / 2,
+                    false => 0,
+                };
+
+                self.award(pot - low_pot, board, &contenders, &highs, contested, events);
+                self.award(low_pot, board, &contenders, &lows, contested, events);
+            }
+        }
+
+        self.phase = Phase::Finished;
+    }
+
+    /// Gives `pot` to the contenders with the best rank on `board`, split evenly between ties
+    fn award(
+        &mut self,
+        pot: usize,
+        board: usize,
+        contenders: &[usize],
+        ranks: &[Option<Rank>],
+        contested: bool,
+        events: &mut Vec<Event>,
+    ) {
+        if pot == 0 {
+            return;
+        }
+
+        let best = contenders
+            .iter()
+            .map(|&seat| ranks[seat])
+            .max()
+            .expect("Nobody is left to win the pot");
+
+        // odd chips go to the first winner left of the button
+        let len = self.players.len();
+        let winners: Vec<usize> = (1..=len)
+            .map(|offset| (self.positions.button + offset) % len)
+            .filter(|seat| contenders.contains(seat) && ranks[*seat] == best)
+            .collect();
+
+        let share = pot / winners.len();
+        let odd_chips = pot % winners.len();
+
+        for (position, &seat) in winners.iter().enumerate() {
+            let amount = if position == 0 {
+                share + odd_chips
+            } else {
+                share
+            };
+
+            self.players[seat].chips += amount;
+
+            let hand = contested.then(|| {
+                let mut cards = self.players[seat].hand.clone();
+                self.boards[board].cards.iter().for_each(|&c| cards.push(c));
+                cards
+            });
+            events.push(Event::PotWon { seat, amount, hand });
+        }
+    }
+}
+
+/// The player on `seat` if they are still in the hand and it is their turn
+fn player_to_act(
+    state: &HandState,
+    seat: usize,
+    to_act: Option<usize>,
+) -> Result<&Player, IllegalAction> {
+    let player = state
+        .players
+        .get(seat)
+        .filter(|p| p.is_playing())
+        .ok_or(IllegalAction::NoSuchSeat { seat })?;
+
+    if player.folded {
+        return Err(IllegalAction::AlreadyFolded { seat });
+    }
+
+    if to_act != Some(seat) {
+        return Err(IllegalAction::OutOfTurn { seat, to_act });
+    }
+
+    Ok(player)
+}
+
+/// Checks whether `seat` is allowed to take `action` right now
+pub fn validate(
+    state: &HandState,
+    seat: usize,
+    action: &PlayerAction,
+) -> Result<(), IllegalAction> {
+    let player = player_to_act(state, seat, state.to_act)?;
+
+    let call_amount = state.call_amount(seat);
+    let current_bet = state.current_bet();
+    let stack = player.bet + player.chips;
+
+    match *action {
+        PlayerAction::Check if call_amount > 0 => {
+            Err(IllegalAction::CheckFacingBet { call_amount })
+        }
+        PlayerAction::Bet(_) if current_bet > 0 => Err(IllegalAction::BetFacingBet { current_bet }),
+        PlayerAction::RaiseTo(_) if current_bet == 0 => Err(IllegalAction::NothingToRaise),
+        PlayerAction::Bet(to) | PlayerAction::RaiseTo(to) if to > stack => {
+            Err(IllegalAction::BetAboveStack {
+                amount: to,
+                maximum: stack,
+            })
+        }
+        PlayerAction::Bet(to) | PlayerAction::RaiseTo(to) => {
+            let (minimum, maximum) = state.raise_bounds(seat).ok_or(IllegalAction::CannotRaise)?;
+
+            if to > maximum {
+                return Err(IllegalAction::RaiseAboveMaximum {
+                    amount: to,
+                    maximum,
+                });
+            }
+
+            // going all in is allowed for less than a full raise
+            if to < minimum && to < stack {
+                return Err(IllegalAction::RaiseBelowMinimum {
+                    amount: to,
+                    minimum,
+                });
+            }
+
+            Ok(())
+        }
+        // all in for more than a call is a raise
+        PlayerAction::AllIn if stack > current_bet => match state.raise_bounds(seat) {
+            None => Err(IllegalAction::CannotRaise),
+            Some((_, maximum)) if stack > maximum => Err(IllegalAction::RaiseAboveMaximum {
+                amount: stack,
+                maximum,
+            }),
+            Some(_) => Ok(()),
+        },
+        PlayerAction::Check | PlayerAction::Call | PlayerAction::AllIn | PlayerAction::Fold => {
+            Ok(())
+        }
+    }
+}
+
+/// Checks whether `seat` is allowed to throw away the cards in `discard` right now
+pub fn validate_draw(
+    state: &HandState,
+    seat: usize,
+    discard: &Discard,
+) -> Result<(), IllegalAction> {
+    let player = player_to_act(state, seat, state.to_draw)?;
+
+    let cards = player.hand.cards.len();
+    if let Some(position) = (cards..32).find(|&p| discard.contains(p)) {
+        return Err(IllegalAction::NoSuchCard { position });
+    }
+
+    if discard.count() > state.draw_limit {
+        return Err(IllegalAction::TooManyDiscards {
+            count: discard.count(),
+            maximum: state.draw_limit,
+        });
+    }
+
+    if !state.replace_discards && discard.count() < state.draw_limit {
+        return Err(IllegalAction::TooFewDiscards {
+            count: discard.count(),
+            required: state.draw_limit,
+        });
+    }
+
+    Ok(())
+}
+
+/// Actions that can be applied to `state` right now.
+///
+/// For the player to act bets and raises are listed with the minimum amount,
+/// any amount up to the maximum of `HandState::raise_bounds` is legal as well.
+/// In a draw `HandState::default_discard` is listed, any other discard up to
+/// `HandState::draw_limit` cards is legal too.
+pub fn legal_actions(state: &HandState) -> Vec<Action> {
+    match state.phase {
+        Phase::Blinds => vec![Action::PostBlinds],
+        Phase::Deal => vec![Action::Deal],
+        Phase::Showdown => vec![Action::Showdown],
+        Phase::Finished => vec![],
+        Phase::Draw => match state.to_draw {
+            Some(seat) => vec![Action::Draw(seat, state.default_discard())],
+            None => vec![],
+        },
+        Phase::Betting => {
+            let Some(seat) = state.to_act else {
+                return vec![];
+            };
+
+            let player = &state.players[seat];
+            let stack = player.bet + player.chips;
+            let current_bet = state.current_bet();
+
+            let mut actions = vec![];
+            if state.call_amount(seat) == 0 {
+                actions.push(Action::Act(seat, PlayerAction::Check));
+            } else {
+                actions.push(Action::Act(seat, PlayerAction::Call));
+            }
+
+            if let Some((minimum, maximum)) = state.raise_bounds(seat) {
+                if stack > minimum {
+                    actions.push(Action::Act(
+                        seat,
+                        PlayerAction::bet_or_raise_to(minimum, current_bet),
+                    ));
+                }
+                if stack <= maximum {
+                    actions.push(Action::Act(seat, PlayerAction::AllIn));
+                }
+            }
+
+            actions.push(Action::Act(seat, PlayerAction::Fold));
+            actions
+        }
+    }
+}
+
+/// Moves the hand forward by one step
+pub fn apply(state: &HandState, action: Action) -> Result<(HandState, Vec<Event>), IllegalAction> {
+    let mut state = state.clone();
+    let mut events = vec![];
+
+    match (state.phase, action) {
+        (Phase::Blinds, Action::PostBlinds) => state.post_forced_bets(&mut events),
+        (Phase::Deal, Action::Deal) => state.deal_street(&mut events)?,
+        (_, Action::Act(seat, action)) => {
+            validate(&state, seat, &action)?;
+            state.act(seat, action, &mut events);
+        }
+        (Phase::Draw, Action::Draw(seat, discard)) => {
+            validate_draw(&state, seat, &discard)?;
+            state.draw(seat, discard, &mut events)?;
+        }
+        (Phase::Draw, Action::TimeOut(seat)) => {
+            let discard = state.default_discard();
+            validate_draw(&state, seat, &discard)?;
+
+            events.push(Event::TimedOut { seat });
+            state.draw(seat, discard, &mut events)?;
+        }
+        (_, Action::TimeOut(seat)) => {
+            validate(&state, seat, &PlayerAction::Fold)?;
+
+            let action = if state.call_amount(seat) == 0 {
+                PlayerAction::Check
+            } else {
+                PlayerAction::Fold
+            };
+
+            events.push(Event::TimedOut { seat });
+            state.act(seat, action, &mut events);
+        }
+        (Phase::Showdown, Action::Showdown) => state.showdown(&mut events),
+        _ => return Err(IllegalAction::OutOfOrder),
+    }
+
+    state.history.extend(events.iter().cloned());
+    Ok((state, events))
+}
+
+/// A hand of `variant` at 2000 blinds with a player for every stack, the button on the first seat
+#[cfg(test)]
+pub(crate) fn new_game(variant: Arc<dyn Variant>, stacks: &[usize]) -> HandState {
+    let players = stacks
+        .iter()
+        .enumerate()
+        .map(|(seat, &chips)| Player::new(format!("Player {}", seat), chips))
+        .collect::<Vec<Player>>();
+    let live = vec![true; players.len()];
+    let positions = Positions::first(&live, 0).unwrap();
+
+    HandState::new(
+        variant,
+        players,
+        positions,
+        2000,
+        CardStack::standard_deck(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{
+        context::GameContext,
+        settings::GameType,
+        variant::Evaluator,
+        variants::{
+            Courchevel, CrazyPineapple, DeuceToSeven, FiveCardDraw, Holdem, Irish, Omaha,
+            Pineapple, Razz, SevenCardStud, StudHiLo,
+        },
+    };
+    use crate::*;
+
+    fn new_hand(stacks: &[usize]) -> HandState {
+        new_game(Arc::new(Holdem::default()), stacks)
+    }
+
+    /// Deck that deals `cards` first, in order
+    fn stacked_deck(cards: &[Card]) -> CardStack {
+        let mut deck = CardStack::standard_deck();
+        deck.cards.retain(|card| !cards.contains(card));
+        deck.cards.extend(cards.iter().rev());
+        deck
+    }
+
+    fn run(state: HandState, actions: &[Action]) -> HandState {
+        actions.iter().fold(state, |state, &action| {
+            apply(&state, action).expect("action should be legal").0
+        })
+    }
+
+    #[test]
+    fn test_blinds_and_deal() {
+        let (state, events) = apply(&new_hand(&[20000, 20000, 20000]), Action::PostBlinds).unwrap();
+        assert_eq!(
+            events[0],
+            Event::HandStarted {
+                game: "No Limit Texas Hold'em".to_string(),
+                button: 0,
+                players: vec![
+                    "Player 0".to_string(),
+                    "Player 1".to_string(),
+                    "Player 2".to_string()
+                ],
+                dealt_in: vec![true; 3],
+            }
+        );
+
+        assert_eq!(state.players[1].bet, 1000);
+        assert_eq!(state.players[2].bet, 2000);
+        assert_eq!(state.phase, Phase::Deal);
+
+        let (state, events) = apply(&state, Action::Deal).unwrap();
+        assert!(state.players.iter().all(|p| p.hand.cards.len() == 2));
+        assert_eq!(state.deck.cards.len(), 46);
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[3], Event::StreetStarted { street: "Preflop" });
+        // hole cards are only for their owner
+        assert!(matches!(events[0], Event::HoleCards { seat: 0, .. }));
+        assert!(events[0].is_visible_to(0));
+        assert!(!events[0].is_visible_to(1));
+        assert!(!events[0].is_public());
+        assert_eq!(state.to_act, Some(0));
+    }
+
+    #[test]
+    fn test_transitions_out_of_order() {
+        let state = new_hand(&[20000, 20000]);
+
+        assert_eq!(
+            apply(&state, Action::Deal).unwrap_err(),
+            IllegalAction::OutOfOrder
+        );
+        assert_eq!(
+            apply(&state, Action::Act(0, PlayerAction::Call)).unwrap_err(),
+            IllegalAction::OutOfTurn {
+                seat: 0,
+                to_act: None
+            }
+        );
+        assert_eq!(legal_actions(&state), vec![Action::PostBlinds]);
+    }
+
+    #[test]
+    fn test_heads_up_order() {
+        let state = run(
+            new_hand(&[20000, 20000]),
+            &[Action::PostBlinds, Action::Deal],
+        );
+
+        // the button posts the small blind and acts first preflop
+        assert_eq!(state.to_act, Some(0));
+
+        let state = run(
+            state,
+            &[
+                Action::Act(0, PlayerAction::Call),
+                Action::Act(1, PlayerAction::Check),
+                Action::Deal,
+            ],
+        );
+
+        assert_eq!(state.street_name(), "Flop");
+        assert_eq!(state.boards[0].cards.len(), 3);
+        // and acts last after the flop
+        assert_eq!(state.to_act, Some(1));
+    }
+
+    #[test]
+    fn test_fold_ends_hand() {
+        let state = run(
+            new_hand(&[20000, 20000, 20000]),
+            &[
+                Action::PostBlinds,
+                Action::Deal,
+                Action::Act(0, PlayerAction::Fold),
+                Action::Act(1, PlayerAction::Fold),
+            ],
+        );
+
+        assert_eq!(state.phase, Phase::Showdown);
+
+        let (state, events) = apply(&state, Action::Showdown).unwrap();
+        assert_eq!(state.phase, Phase::Finished);
+        assert_eq!(state.players[2].chips, 21000);
+        assert_eq!(
+            events,
+            vec![Event::PotWon {
+                seat: 2,
+                amount: 3000,
+                hand: None
+            }]
+        );
+    }
+
+    #[test]
+    fn test_raise_reopens_action() {
+        let state = run(
+            new_hand(&[20000, 20000, 20000]),
+            &[
+                Action::PostBlinds,
+                Action::Deal,
+                Action::Act(0, PlayerAction::Call),
+                Action::Act(1, PlayerAction::Call),
+                Action::Act(2, PlayerAction::RaiseTo(6000)),
+            ],
+        );
+
+        assert_eq!(state.to_act, Some(0));
+        assert_eq!(state.call_amount(0), 4000);
+        assert_eq!(state.min_raise, 4000);
+
+        assert_eq!(
+            legal_actions(&state),
+            vec![
+                Action::Act(0, PlayerAction::Call),
+                Action::Act(0, PlayerAction::RaiseTo(10000)),
+                Action::Act(0, PlayerAction::AllIn),
+                Action::Act(0, PlayerAction::Fold),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_all_in_runs_out_the_board() {
+        let mut state = run(
+            new_hand(&[5000, 20000]),
+            &[
+                Action::PostBlinds,
+                Action::Deal,
+                Action::Act(0, PlayerAction::AllIn),
+                Action::Act(1, PlayerAction::Call),
+            ],
+        );
+
+        // nobody can bet anymore, the remaining streets are only dealt
+        for street in ["Flop", "Turn", "River"] {
+            assert_eq!(state.street_name(), street);
+            assert_eq!(legal_actions(&state), vec![Action::Deal]);
+            state = apply(&state, Action::Deal).unwrap().0;
+        }
+
+        assert_eq!(state.phase, Phase::Showdown);
+        let state = apply(&state, Action::Showdown).unwrap().0;
+
+        assert_eq!(state.players.iter().map(|p| p.chips).sum::<usize>(), 25000);
+    }
+
+    #[test]
+    fn test_side_pots() {
+        let mut state = new_hand(&[1000, 20000, 20000]);
+        state.players[0].hand = CardStack::from(vec![HEART | ACE, SPADE | ACE]);
+        state.players[1].hand = CardStack::from(vec![HEART | KING, SPADE | KING]);
+        state.players[2].hand = CardStack::from(vec![HEART | TWO, SPADE | SEVEN]);
+        state.boards[0] = CardStack::from(vec![
+            CLUB | THREE,
+            DIAMOND | FOUR,
+            CLUB | NINE,
+            DIAMOND | JACK,
+            CLUB | QUEEN,
+        ]);
+        state.players[0].total_bet = 1000;
+        state.players[0].chips = 0;
+        state.players[1].total_bet = 5000;
+        state.players[1].chips = 15000;
+        state.players[2].total_bet = 5000;
+        state.players[2].chips = 15000;
+        state.phase = Phase::Showdown;
+        assert_eq!(state.pots(), vec![3000, 8000]);
+
+        let (state, _) = apply(&state, Action::Showdown).unwrap();
+
+        // the aces win the main pot, the kings the side pot
+        assert_eq!(state.players[0].chips, 3000);
+        assert_eq!(state.players[1].chips, 23000);
+        assert_eq!(state.players[2].chips, 15000);
+    }
+
+    #[test]
+    fn test_validate_actions() {
+        let mut state = new_hand(&[20000, 20000, 20000]);
+
+        state.players[0].bet = 2000;
+        state.players[0].total_bet = 2000;
+        state.players[0].chips -= 2000;
+        state.to_act = Some(1);
+
+        assert_eq!(
+            validate(&state, 1, &PlayerAction::Check),
+            Err(IllegalAction::CheckFacingBet { call_amount: 2000 })
+        );
+        assert_eq!(
+            validate(&state, 1, &PlayerAction::RaiseTo(30000)),
+            Err(IllegalAction::BetAboveStack {
+                amount: 30000,
+                maximum: 20000
+            })
+        );
+        assert_eq!(
+            validate(&state, 1, &PlayerAction::Bet(4000)),
+            Err(IllegalAction::BetFacingBet { current_bet: 2000 })
+        );
+        assert_eq!(
+            validate(&state, 1, &PlayerAction::RaiseTo(3000)),
+            Err(IllegalAction::RaiseBelowMinimum {
+                amount: 3000,
+                minimum: 4000
+            })
+        );
+        assert_eq!(validate(&state, 1, &PlayerAction::RaiseTo(4000)), Ok(()));
+        assert_eq!(validate(&state, 1, &PlayerAction::AllIn), Ok(()));
+        assert_eq!(validate(&state, 1, &PlayerAction::Call), Ok(()));
+        assert_eq!(validate(&state, 1, &PlayerAction::Fold), Ok(()));
+
+        assert_eq!(
+            validate(&state, 2, &PlayerAction::Call),
+            Err(IllegalAction::OutOfTurn {
+                seat: 2,
+                to_act: Some(1)
+            })
+        );
+        assert_eq!(
+            validate(&state, 7, &PlayerAction::Call),
+            Err(IllegalAction::NoSuchSeat { seat: 7 })
+        );
+
+        state.players[2].folded = true;
+        assert_eq!(
+            validate(&state, 2, &PlayerAction::Call),
+            Err(IllegalAction::AlreadyFolded { seat: 2 })
+        );
+
+        // all in for less than a full raise
+        state.players[1].chips = 3000;
+        assert_eq!(validate(&state, 1, &PlayerAction::RaiseTo(3000)), Ok(()));
+    }
+
+    #[test]
+    fn test_bets_are_per_street() {
+        let state = run(
+            new_hand(&[20000, 20000, 20000]),
+            &[
+                Action::PostBlinds,
+                Action::Deal,
+                Action::Act(0, PlayerAction::Call),
+                Action::Act(1, PlayerAction::Call),
+                Action::Act(2, PlayerAction::Check),
+                Action::Deal,
+            ],
+        );
+
+        assert_eq!(state.current_bet(), 0);
+        assert_eq!(state.pot(), 6000);
+        assert_eq!(
+            apply(&state, Action::Act(1, PlayerAction::RaiseTo(4000))).unwrap_err(),
+            IllegalAction::NothingToRaise
+        );
+
+        let (state, events) = apply(&state, Action::Act(1, PlayerAction::Bet(3000))).unwrap();
+        assert_eq!(
+            events,
+            vec![Event::Bet {
+                seat: 1,
+                amount: 3000
+            }]
+        );
+        assert_eq!(state.pot(), 9000);
+        assert_eq!(
+            legal_actions(&state)[1],
+            Action::Act(2, PlayerAction::RaiseTo(6000))
+        );
+    }
+
+    #[test]
+    fn test_time_out_checks_or_folds() {
+        let state = run(
+            new_hand(&[20000, 20000, 20000]),
+            &[Action::PostBlinds, Action::Deal],
+        );
+
+        let (state, events) = apply(&state, Action::TimeOut(0)).unwrap();
+        assert_eq!(
+            events,
+            vec![Event::TimedOut { seat: 0 }, Event::Folded { seat: 0 }]
+        );
+
+        let state = run(state, &[Action::Act(1, PlayerAction::Call)]);
+        let (_, events) = apply(&state, Action::TimeOut(2)).unwrap();
+        assert_eq!(
+            events[..2],
+            [Event::TimedOut { seat: 2 }, Event::Checked { seat: 2 }]
+        );
+
+        assert_eq!(
+            apply(&state, Action::TimeOut(1)).unwrap_err(),
+            IllegalAction::OutOfTurn {
+                seat: 1,
+                to_act: Some(2)
+            }
+        );
+    }
+
+    #[test]
+    fn test_returning_players_post_owed_blinds() {
+        let mut state = new_hand(&[20000, 20000, 20000, 20000]);
+        state.players[3].owed_blinds = OwedBlinds::Both;
+
+        let (state, events) = apply(&state, Action::PostBlinds).unwrap();
+
+        assert_eq!(
+            events.last(),
+            Some(&Event::OwedBlinds {
+                seat: 3,
+                live: 2000,
+                dead: 1000
+            })
+        );
+        // only the live blind counts as a bet
+        assert_eq!(state.players[3].bet, 2000);
+        assert_eq!(state.players[3].total_bet, 3000);
+        assert_eq!(state.players[3].owed_blinds, OwedBlinds::None);
+        assert_eq!(state.pot(), 6000);
+    }
+
+    #[test]
+    fn test_pot_limit_raises() {
+        let state = run(
+            new_game(
+                Arc::new(Holdem::new(BettingStructure::PotLimit)),
+                &[20000, 20000, 20000],
+            ),
+            &[Action::PostBlinds, Action::Deal],
+        );
+
+        // call 2000 and raise by the 5000 that are in the pot then
+        assert_eq!(state.raise_bounds(0), Some((4000, 7000)));
+        assert_eq!(
+            validate(&state, 0, &PlayerAction::RaiseTo(8000)),
+            Err(IllegalAction::RaiseAboveMaximum {
+                amount: 8000,
+                maximum: 7000
+            })
+        );
+        assert_eq!(
+            validate(&state, 0, &PlayerAction::AllIn),
+            Err(IllegalAction::RaiseAboveMaximum {
+                amount: 20000,
+                maximum: 7000
+            })
+        );
+        assert!(!legal_actions(&state).contains(&Action::Act(0, PlayerAction::AllIn)));
+    }
+
+    #[test]
+    fn test_pot_limit_omaha() {
+        let state = run(
+            new_game(Arc::new(Omaha::new(5)), &[20000, 20000, 20000]),
+            &[Action::PostBlinds, Action::Deal],
+        );
+
+        assert!(state.players.iter().all(|p| p.hand.cards.len() == 5));
+        assert_eq!(GameContext::new(&state, 0).pot_raise, Some(7000));
+
+        let state = run(
+            state,
+            &[
+                Action::Act(0, PlayerAction::RaiseTo(7000)),
+                Action::Act(1, PlayerAction::Fold),
+            ],
+        );
+
+        // 7000 to call, then the pot of 17000 on top
+        assert_eq!(GameContext::new(&state, 2).pot_raise, Some(20000));
+    }
+
+    #[test]
+    fn test_fixed_limit_cap() {
+        let state = run(
+            new_game(
+                Arc::new(Holdem::new(BettingStructure::FixedLimit)),
+                &[20000, 20000, 20000, 20000],
+            ),
+            &[Action::PostBlinds, Action::Deal],
+        );
+
+        assert_eq!(state.raise_bounds(3), Some((4000, 4000)));
+
+        let state = run(
+            state,
+            &[
+                Action::Act(3, PlayerAction::RaiseTo(4000)),
+                Action::Act(0, PlayerAction::RaiseTo(6000)),
+                Action::Act(1, PlayerAction::RaiseTo(8000)),
+            ],
+        );
+
+        // the big blind, a bet and three raises
+        assert_eq!(
+            legal_actions(&state),
+            vec![
+                Action::Act(2, PlayerAction::Call),
+                Action::Act(2, PlayerAction::Fold),
+            ]
+        );
+        assert_eq!(
+            validate(&state, 2, &PlayerAction::RaiseTo(10000)),
+            Err(IllegalAction::CannotRaise)
+        );
+    }
+
+    #[test]
+    fn test_short_all_in_does_not_reopen_raising() {
+        let state = run(
+            new_hand(&[20000, 20000, 5000, 20000]),
+            &[
+                Action::PostBlinds,
+                Action::Deal,
+                Action::Act(3, PlayerAction::RaiseTo(4000)),
+                Action::Act(0, PlayerAction::Call),
+                Action::Act(1, PlayerAction::Fold),
+                // 1000 more is less than the 2000 a raise needs
+                Action::Act(2, PlayerAction::AllIn),
+            ],
+        );
+
+        assert_eq!(state.current_bet(), 5000);
+        assert_eq!(state.raise_bounds(3), None);
+        assert_eq!(
+            legal_actions(&state),
+            vec![
+                Action::Act(3, PlayerAction::Call),
+                Action::Act(3, PlayerAction::Fold),
+            ]
+        );
+        assert_eq!(
+            validate(&state, 3, &PlayerAction::RaiseTo(7000)),
+            Err(IllegalAction::CannotRaise)
+        );
+
+        let state = run(state, &[Action::Act(3, PlayerAction::Call)]);
+        assert_eq!(state.to_act, Some(0));
+        assert_eq!(state.raise_bounds(0), None);
+    }
+
+    #[test]
+    fn test_fixed_limit_short_all_in_is_not_a_raise() {
+        let state = run(
+            new_game(
+                Arc::new(Holdem::new(BettingStructure::FixedLimit)),
+                &[20000, 7000, 20000, 20000],
+            ),
+            &[
+                Action::PostBlinds,
+                Action::Deal,
+                Action::Act(3, PlayerAction::RaiseTo(4000)),
+                Action::Act(0, PlayerAction::RaiseTo(6000)),
+                Action::Act(1, PlayerAction::AllIn),
+            ],
+        );
+
+        // the big blind, a bet and a raise, the all in for 1000 more leaves room under the cap
+        assert_eq!(state.current_bet(), 7000);
+        assert_eq!(state.raises, 3);
+        assert!(state.raise_bounds(2).is_some());
+    }
+
+    struct HoldemHiLo;
+
+    impl Variant for HoldemHiLo {
+        fn game_type(&self) -> GameType {
+            GameType::TexasHoldem
+        }
+
+        fn name(&self) -> String {
+            "Hold'em Hi-Lo".to_string()
+        }
+
+        fn streets(&self) -> Vec<Street> {
+            Holdem::default().streets()
+        }
+
+        fn betting(&self) -> BettingStructure {
+            BettingStructure::NoLimit
+        }
+
+        fn evaluator(&self) -> Evaluator {
+            Evaluator::HiLo
+        }
+    }
+
+    #[test]
+    fn test_split_pot() {
+        let mut state = new_game(Arc::new(HoldemHiLo), &[20000, 20000, 20000]);
+        state.players[0].hand = CardStack::from(vec![HEART | KING, SPADE | KING]);
+        state.players[1].hand = CardStack::from(vec![HEART | ACE, SPADE | TWO]);
+        state.players[2].hand = CardStack::from(vec![HEART | QUEEN, SPADE | JACK]);
+        state.boards[0] = CardStack::from(vec![
+            CLUB | THREE,
+            DIAMOND | FOUR,
+            CLUB | KING,
+            DIAMOND | SEVEN,
+            CLUB | NINE,
+        ]);
+        for player in state.players.iter_mut() {
+            player.total_bet = 5001;
+            player.chips -= 5001;
+        }
+        state.phase = Phase::Showdown;
+
+        let (state, _) = apply(&state, Action::Showdown).unwrap();
+
+        // the kings scoop the high half with the odd chip, the ace-deuce makes the only low
+        assert_eq!(state.players[0].chips, 14999 + 7502);
+        assert_eq!(state.players[1].chips, 14999 + 7501);
+        assert_eq!(state.players[2].chips, 14999);
+    }
+
+    #[test]
+    fn test_courchevel_exposes_a_flop_card() {
+        let state = run(
+            new_game(Arc::new(Courchevel::default()), &[20000, 20000]),
+            &[Action::PostBlinds, Action::Deal],
+        );
+
+        // the first card of the flop is out before anybody acts
+        assert_eq!(state.phase, Phase::Betting);
+        assert_eq!(state.street_name(), "Preflop");
+        assert_eq!(state.boards[0].cards.len(), 1);
+        assert_eq!(state.players[0].hand.cards.len(), 5);
+        assert_eq!(GameContext::new(&state, 0).boards, state.boards);
+
+        let exposed = state.boards[0].cards[0];
+        let state = run(
+            state,
+            &[
+                Action::Act(0, PlayerAction::Call),
+                Action::Act(1, PlayerAction::Check),
+                Action::Deal,
+            ],
+        );
+
+        assert_eq!(state.street_name(), "Flop");
+        assert_eq!(state.boards[0].cards.len(), 3);
+        assert_eq!(state.boards[0].cards[0], exposed);
+    }
+
+    #[test]
+    fn test_irish_discards_two_after_the_flop() {
+        let state = run(
+            new_game(Arc::new(Irish::default()), &[20000, 20000]),
+            &[
+                Action::PostBlinds,
+                Action::Deal,
+                Action::Act(0, PlayerAction::Call),
+                Action::Act(1, PlayerAction::Check),
+                Action::Deal,
+                Action::Act(1, PlayerAction::Check),
+                Action::Act(0, PlayerAction::Check),
+                Action::Deal,
+            ],
+        );
+
+        assert_eq!(state.phase, Phase::Draw);
+        assert_eq!(state.boards[0].cards.len(), 3);
+        assert_eq!(GameContext::new(&state, 1).discard, Some(2));
+        assert_eq!(
+            validate_draw(&state, 1, &Discard::positions(&[0])),
+            Err(IllegalAction::TooFewDiscards {
+                count: 1,
+                required: 2
+            })
+        );
+
+        let state = run(
+            state,
+            &[
+                Action::Draw(1, Discard::positions(&[0, 3])),
+                Action::TimeOut(0),
+            ],
+        );
+
+        assert_eq!(state.phase, Phase::Betting);
+        assert_eq!(state.street_name(), "Turn");
+        assert_eq!(state.boards[0].cards.len(), 4);
+        assert_eq!(state.muck.cards.len(), 4);
+        assert!(state.players.iter().all(|p| p.hand.cards.len() == 2));
+    }
+
+    #[test]
+    fn test_double_board() {
+        let (state, events) = apply(
+            &run(
+                new_game(Arc::new(Holdem::default().double_board()), &[20000, 20000]),
+                &[
+                    Action::PostBlinds,
+                    Action::Deal,
+                    Action::Act(0, PlayerAction::Call),
+                    Action::Act(1, PlayerAction::Check),
+                ],
+            ),
+            Action::Deal,
+        )
+        .unwrap();
+
+        let flops: Vec<usize> = events
+            .iter()
+            .filter_map(|event| match event {
+                Event::Community { board, cards, .. } if cards.cards.len() == 3 => Some(*board),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(flops, vec![0, 1]);
+        assert!(state.boards[0]
+            .cards
+            .iter()
+            .all(|card| !state.boards[1].cards.contains(card)));
+
+        let mut state = new_game(
+            Arc::new(Holdem::default().double_board()),
+            &[20000, 20000, 20000],
+        );
+        state.players[0].hand = CardStack::from(vec![HEART | ACE, SPADE | ACE]);
+        state.players[1].hand = CardStack::from(vec![HEART | KING, SPADE | KING]);
+        state.players[2].folded = true;
+        state.boards = vec![
+            CardStack::from(vec![
+                CLUB | THREE,
+                DIAMOND | FOUR,
+                CLUB | NINE,
+                DIAMOND | JACK,
+                CLUB | QUEEN,
+            ]),
+            CardStack::from(vec![
+                CLUB | KING,
+                HEART | FOUR,
+                DIAMOND | NINE,
+                SPADE | TWO,
+                CLUB | SEVEN,
+            ]),
+        ];
+        for player in state.players.iter_mut() {
+            let bet = if player.folded { 1001 } else { 5001 };
+            player.total_bet = bet;
+            player.chips -= bet;
+        }
+        state.phase = Phase::Showdown;
+
+        let (state, events) = apply(&state, Action::Showdown).unwrap();
+
+        // the aces win the first board with the odd chip, the set of kings the second one
+        assert_eq!(state.players[0].chips, 14999 + 5502);
+        assert_eq!(state.players[1].chips, 14999 + 5501);
+        assert_eq!(state.players[2].chips, 18999);
+        assert!(events.contains(&Event::PotWon {
+            seat: 1,
+            amount: 5501,
+            hand: Some(CardStack::from(vec![
+                HEART | KING,
+                SPADE | KING,
+                CLUB | KING,
+                HEART | FOUR,
+                DIAMOND | NINE,
+                SPADE | TWO,
+                CLUB | SEVEN,
+            ])),
+        }));
+    }
+
+    #[test]
+    fn test_double_board_omaha() {
+        let mut state = new_game(
+            GameType::DoubleBoardOmaha.variant().unwrap(),
+            &[20000, 20000],
+        );
+        assert_eq!(state.boards.len(), 2);
+
+        state.players[0].hand =
+            CardStack::from(vec![HEART | ACE, SPADE | ACE, CLUB | TWO, DIAMOND | THREE]);
+        state.players[1].hand =
+            CardStack::from(vec![HEART | KING, SPADE | KING, CLUB | FIVE, DIAMOND | SIX]);
+        state.boards = vec![
+            CardStack::from(vec![
+                CLUB | THREE,
+                DIAMOND | FOUR,
+                CLUB | NINE,
+                DIAMOND | JACK,
+                HEART | QUEEN,
+            ]),
+            CardStack::from(vec![
+                HEART | TWO,
+                SPADE | SEVEN,
+                DIAMOND | EIGHT,
+                CLUB | TEN,
+                SPADE | FIVE,
+            ]),
+        ];
+        for player in state.players.iter_mut() {
+            player.total_bet = 5000;
+            player.chips -= 5000;
+        }
+        state.phase = Phase::Showdown;
+
+        let (state, events) = apply(&state, Action::Showdown).unwrap();
+
+        // the aces scoop both boards
+        assert_eq!(state.players[0].chips, 25000);
+        assert_eq!(state.players[1].chips, 15000);
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| matches!(
+                    event,
+                    Event::PotWon {
+                        seat: 0,
+                        amount: 5000,
+                        ..
+                    }
+                ))
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_draw() {
+        let state = run(
+            new_game(Arc::new(FiveCardDraw::default()), &[20000, 20000, 20000]),
+            &[
+                Action::PostBlinds,
+                Action::Deal,
+                Action::Act(0, PlayerAction::Call),
+                Action::Act(1, PlayerAction::Call),
+                Action::Act(2, PlayerAction::Check),
+                Action::Deal,
+            ],
+        );
+
+        assert_eq!(state.phase, Phase::Draw);
+        assert_eq!(state.to_draw, Some(1));
+        assert_eq!(
+            legal_actions(&state),
+            vec![Action::Draw(1, Discard::stand_pat())]
+        );
+        assert_eq!(
+            apply(&state, Action::Draw(2, Discard::stand_pat())).unwrap_err(),
+            IllegalAction::OutOfTurn {
+                seat: 2,
+                to_act: Some(1)
+            }
+        );
+        assert_eq!(
+            validate_draw(&state, 1, &Discard::positions(&[5])),
+            Err(IllegalAction::NoSuchCard { position: 5 })
+        );
+
+        let kept = state.players[1].hand.cards[2..].to_vec();
+        let (state, events) = apply(&state, Action::Draw(1, Discard::positions(&[0, 1]))).unwrap();
+
+        assert_eq!(events[0], Event::Drew { seat: 1, count: 2 });
+        assert_eq!(state.players[1].hand.cards.len(), 5);
+        assert_eq!(state.players[1].hand.cards[..3], kept[..]);
+        assert_eq!(state.players[1].draws, vec![2]);
+        assert_eq!(state.muck.cards.len(), 2);
+
+        let state = run(
+            state,
+            &[
+                Action::TimeOut(2),
+                Action::Draw(0, Discard::positions(&[4])),
+            ],
+        );
+
+        // the betting after the draw starts left of the button
+        assert_eq!(state.players[2].draws, vec![0]);
+        assert_eq!(state.phase, Phase::Betting);
+        assert_eq!(state.to_act, Some(1));
+        assert_eq!(state.bet_size(), 4000);
+    }
+
+    #[test]
+    fn test_pineapple_discard() {
+        let state = run(
+            new_game(Arc::new(Pineapple::default()), &[20000, 20000, 20000]),
+            &[
+                Action::PostBlinds,
+                Action::Deal,
+                Action::Act(0, PlayerAction::Call),
+                Action::Act(1, PlayerAction::Call),
+                Action::Act(2, PlayerAction::Check),
+                Action::Deal,
+            ],
+        );
+
+        // the discard comes before the flop is dealt
+        assert_eq!(state.phase, Phase::Draw);
+        assert_eq!(state.street_name(), "Flop");
+        assert!(state.boards[0].cards.is_empty());
+        assert_eq!(state.players[1].hand.cards.len(), 3);
+        assert_eq!(
+            legal_actions(&state),
+            vec![Action::Draw(1, Discard::positions(&[0]))]
+        );
+        assert_eq!(
+            validate_draw(&state, 1, &Discard::stand_pat()),
+            Err(IllegalAction::TooFewDiscards {
+                count: 0,
+                required: 1
+            })
+        );
+        assert_eq!(
+            validate_draw(&state, 1, &Discard::positions(&[0, 1])),
+            Err(IllegalAction::TooManyDiscards {
+                count: 2,
+                maximum: 1
+            })
+        );
+
+        let context = GameContext::new(&state, 1);
+        assert_eq!(context.discard, Some(1));
+        assert_eq!(context.draw, None);
+
+        let thrown = state.players[1].hand.cards[2];
+        let (state, events) = apply(&state, Action::Draw(1, Discard::positions(&[2]))).unwrap();
+
+        assert_eq!(events[0], Event::Discarded { seat: 1, count: 1 });
+        assert_eq!(state.players[1].hand.cards.len(), 2);
+        assert!(!state.players[1].hand.cards.contains(&thrown));
+        assert_eq!(state.muck.cards, vec![thrown]);
+
+        let context = GameContext::new(&state, 2);
+        assert_eq!(context.players[1].hand.cards, vec![CONCEALED; 2]);
+        assert!(!context.players[1].hand.cards.contains(&thrown));
+
+        let state = run(
+            state,
+            &[
+                Action::TimeOut(2),
+                Action::Draw(0, Discard::positions(&[1])),
+            ],
+        );
+
+        assert_eq!(state.phase, Phase::Betting);
+        assert_eq!(state.boards[0].cards.len(), 3);
+        assert!(state.players.iter().all(|p| p.hand.cards.len() == 2));
+    }
+
+    #[test]
+    fn test_crazy_pineapple_discards_after_the_flop() {
+        let state = run(
+            new_game(Arc::new(CrazyPineapple::default()), &[20000, 20000]),
+            &[
+                Action::PostBlinds,
+                Action::Deal,
+                Action::Act(0, PlayerAction::Call),
+                Action::Act(1, PlayerAction::Check),
+                Action::Deal,
+            ],
+        );
+
+        assert_eq!(state.phase, Phase::Betting);
+        assert_eq!(state.boards[0].cards.len(), 3);
+        assert_eq!(state.players[0].hand.cards.len(), 3);
+
+        let state = run(
+            state,
+            &[
+                Action::Act(1, PlayerAction::Check),
+                Action::Act(0, PlayerAction::Check),
+                Action::Deal,
+            ],
+        );
+
+        assert_eq!(state.phase, Phase::Draw);
+        assert_eq!(state.boards[0].cards.len(), 3);
+        assert_eq!(state.street_name(), "Turn");
+    }
+
+    #[test]
+    fn test_triple_draw() {
+        let state = run(
+            new_game(Arc::new(DeuceToSeven::triple_draw()), &[20000, 20000]),
+            &[
+                Action::PostBlinds,
+                Action::Deal,
+                Action::Act(0, PlayerAction::Call),
+                Action::Act(1, PlayerAction::Check),
+                Action::Deal,
+                Action::Draw(1, Discard::stand_pat()),
+                Action::Draw(0, Discard::positions(&[0, 4])),
+            ],
+        );
+
+        // standing pat is public
+        let context = GameContext::new(&state, 0);
+        assert!(context.players[1].stood_pat());
+        assert!(!context.players[0].stood_pat());
+        assert_eq!(context.players[0].draws, vec![2]);
+        assert_eq!(context.evaluator, Evaluator::DeuceToSeven);
+
+        let mut state = state;
+        for draw in 2..=3 {
+            state = run(
+                state,
+                &[
+                    Action::Act(1, PlayerAction::Check),
+                    Action::Act(0, PlayerAction::Check),
+                    Action::Deal,
+                    Action::TimeOut(1),
+                    Action::TimeOut(0),
+                ],
+            );
+            assert_eq!(state.players[0].draws.len(), draw);
+            assert_eq!(state.bet_size(), 4000);
+        }
+
+        let state = run(
+            state,
+            &[
+                Action::Act(1, PlayerAction::Check),
+                Action::Act(0, PlayerAction::Check),
+            ],
+        );
+        assert_eq!(state.phase, Phase::Showdown);
+    }
+
+    #[test]
+    fn test_badugi_showdown() {
+        let mut state = new_game(GameType::Badugi.variant().unwrap(), &[20000, 20000]);
+        assert_eq!(state.streets.len(), 4);
+
+        // a king high badugi beats three cards to a wheel
+        state.players[0].hand = CardStack::from(vec![
+            HEART | KING,
+            CLUB | TWO,
+            SPADE | THREE,
+            DIAMOND | FOUR,
+        ]);
+        state.players[1].hand =
+            CardStack::from(vec![HEART | ACE, CLUB | TWO, SPADE | THREE, SPADE | FOUR]);
+        for player in state.players.iter_mut() {
+            player.total_bet = 4000;
+            player.chips -= 4000;
+        }
+        state.phase = Phase::Showdown;
+
+        let (state, _) = apply(&state, Action::Showdown).unwrap();
+
+        assert_eq!(state.players[0].chips, 24000);
+        assert_eq!(state.players[1].chips, 16000);
+    }
+
+    #[test]
+    fn test_draw_reshuffles_discards() {
+        let mut state = run(
+            new_game(Arc::new(FiveCardDraw::default()), &[20000, 20000]),
+            &[
+                Action::PostBlinds,
+                Action::Deal,
+                Action::Act(0, PlayerAction::Call),
+                Action::Act(1, PlayerAction::Check),
+                Action::Deal,
+            ],
+        );
+
+        // the stub only has a single card left
+        let stub = state.deck.cards.split_off(1);
+        state.muck.cards = stub[..3].to_vec();
+
+        let state = run(state, &[Action::Draw(1, Discard::positions(&[0, 1, 2]))]);
+
+        assert_eq!(state.players[1].hand.cards.len(), 5);
+        assert_eq!(state.deck.cards.len(), 1);
+        assert_eq!(state.muck.cards.len(), 3);
+    }
+
+    fn stud_hand() -> HandState {
+        stud_game(Arc::new(SevenCardStud::default()))
+    }
+
+    fn stud_game(variant: Arc<dyn Variant>) -> HandState {
+        let mut state = new_game(variant, &[20000, 20000, 20000]);
+        state.deck = stacked_deck(&[
+            // down cards, two rounds
+            HEART | ACE,
+            SPADE | ACE,
+            CLUB | ACE,
+            HEART | KING,
+            SPADE | KING,
+            CLUB | KING,
+            // door cards
+            SPADE | QUEEN,
+            HEART | TWO,
+            CLUB | TWO,
+            // fourth street
+            SPADE | THREE,
+            DIAMOND | TWO,
+            CLUB | FIVE,
+        ]);
+        state
+    }
+
+    #[test]
+    fn test_stud_bring_in() {
+        let (state, events) = apply(&stud_hand(), Action::PostBlinds).unwrap();
+        assert!(matches!(events[0], Event::HandStarted { button: 0, .. }));
+        assert_eq!(
+            events[1],
+            Event::Ante {
+                seat: 0,
+                amount: 200
+            }
+        );
+        assert_eq!(state.pot(), 600);
+
+        let (state, events) = apply(&state, Action::Deal).unwrap();
+
+        // the deuce of clubs is the lowest door card
+        assert_eq!(
+            events.last(),
+            Some(&Event::BringIn {
+                seat: 2,
+                amount: 500
+            })
+        );
+        assert_eq!(state.to_act, Some(0));
+        assert_eq!(state.raise_bounds(0), Some((2000, 2000)));
+
+        // other players only see the door card
+        let context = GameContext::new(&state, 0);
+        assert_eq!(
+            context.players[1].hand.cards,
+            vec![CONCEALED, CONCEALED, HEART | TWO]
+        );
+        assert_eq!(context.hand.cards.len(), 3);
+
+        let state = run(
+            state,
+            &[
+                Action::Act(0, PlayerAction::RaiseTo(2000)),
+                Action::Act(1, PlayerAction::RaiseTo(4000)),
+            ],
+        );
+        assert_eq!(state.raise_bounds(2), Some((6000, 6000)));
+
+        // the bring-in closes the action when nobody completes
+        let state = run(
+            stud_hand(),
+            &[
+                Action::PostBlinds,
+                Action::Deal,
+                Action::Act(0, PlayerAction::Call),
+                Action::Act(1, PlayerAction::Call),
+            ],
+        );
+        assert_eq!(state.phase, Phase::Deal);
+    }
+
+    #[test]
+    fn test_stud_best_board_leads() {
+        let state = run(
+            stud_hand(),
+            &[
+                Action::PostBlinds,
+                Action::Deal,
+                Action::Act(0, PlayerAction::Call),
+                Action::Act(1, PlayerAction::Call),
+                Action::Deal,
+            ],
+        );
+
+        // the pair of deuces showing beats the queen high
+        assert_eq!(state.street_name(), "Fourth street");
+        assert_eq!(state.to_act, Some(1));
+    }
+
+    #[test]
+    fn test_stud_community_card_when_deck_runs_out() {
+        let mut state = run(
+            stud_hand(),
+            &[
+                Action::PostBlinds,
+                Action::Deal,
+                Action::Act(0, PlayerAction::Call),
+                Action::Act(1, PlayerAction::Call),
+            ],
+        );
+        state.deck.cards.drain(..state.deck.cards.len() - 2);
+
+        let (state, events) = apply(&state, Action::Deal).unwrap();
+
+        assert_eq!(
+            events[0],
+            Event::Community {
+                street: "Fourth street",
+                board: 0,
+                cards: CardStack::from(vec![SPADE | THREE])
+            }
+        );
+        assert!(state.players.iter().all(|p| p.hand.cards.len() == 3));
+    }
+
+    #[test]
+    fn test_razz_order() {
+        let (state, events) = apply(&stud_game(Arc::new(Razz::default())), Action::PostBlinds)
+            .and_then(|(state, _)| apply(&state, Action::Deal))
+            .unwrap();
+
+        // the queen is the highest door card
+        assert_eq!(
+            events.last(),
+            Some(&Event::BringIn {
+                seat: 0,
+                amount: 500
+            })
+        );
+
+        let state = run(
+            state,
+            &[
+                Action::Act(1, PlayerAction::Call),
+                Action::Act(2, PlayerAction::Call),
+                Action::Deal,
+            ],
+        );
+
+        // five-deuce is the best low board, the pair of deuces the worst
+        assert_eq!(state.to_act, Some(2));
+    }
+
+    #[test]
+    fn test_stud_hi_lo_scoop() {
+        let mut state = new_game(Arc::new(StudHiLo::default()), &[20000, 20000, 20000]);
+        state.players[0].hand = CardStack::from(vec![
+            HEART | ACE,
+            SPADE | TWO,
+            CLUB | THREE,
+            CLUB | FOUR,
+            DIAMOND | FIVE,
+            SPADE | KING,
+            HEART | NINE,
+        ]);
+        state.players[1].hand = CardStack::from(vec![
+            HEART | KING,
+            CLUB | KING,
+            CLUB | QUEEN,
+            DIAMOND | JACK,
+            DIAMOND | NINE,
+            SPADE | NINE,
+            HEART | SIX,
+        ]);
+        state.players[2].folded = true;
+        for player in state.players.iter_mut().take(2) {
+            player.total_bet = 5000;
+            player.chips -= 5000;
+        }
+        state.phase = Phase::Showdown;
+
+        let (state, events) = apply(&state, Action::Showdown).unwrap();
+
+        // the wheel is the best high and the best low
+        assert_eq!(state.players[0].chips, 25000);
+        assert_eq!(
+            events
+                .iter()
+                .filter(|e| matches!(e, Event::PotWon { seat: 0, .. }))
+                .count(),
+            2
+        );
+    }
+}
@@ -1,5 +1,18 @@
 pub mod actor;
 pub mod context;
+pub mod hand;
+pub mod observer;
 pub mod player;
+pub mod profile;
+pub mod rotation;
+pub mod runner;
+pub mod seats;
 pub mod settings;
+pub mod signals;
+pub mod simulation;
+pub mod stats;
+pub mod strategy;
 pub mod table;
+pub mod validation;
+pub mod variant;
+pub mod variants;
@@ -0,0 +1,7 @@
+use super::hand::Event;
+
+/// Follows a table without playing, like a logger or a statistics tracker.
+/// It only gets told about public events, never about anybody's hole cards.
+pub trait Observer: Send + Sync {
+    fn notify(&self, event: &Event);
+}
@@ -1,13 +1,39 @@
 use crate::CardStack;
 
+/// Blinds a player has to post before being dealt in again
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OwedBlinds {
+    #[default]
+    None,
+    /// New players post a big blind to get a hand right away
+    BigBlind,
+    /// The blinds passed while the player sat out. The big blind is live,
+    /// the small blind goes into the pot as dead money
+    Both,
+}
+
 #[derive(Debug, Clone)]
 pub struct Player {
     pub name: String,
     pub eliminated: bool,
     pub hand: CardStack,
+    /// Hole cards dealt face up, they are part of `hand` as well
+    pub face_up: CardStack,
+    /// Cards drawn in every draw of the hand so far
+    pub draws: Vec<usize>,
     pub chips: usize,
     pub folded: bool,
+    /// Chips in front of the player in the current betting round
     pub bet: usize,
+    /// Chips the player put into the pot this hand, including `bet`
+    pub total_bet: usize,
+    /// Not dealt in until the player comes back
+    pub sitting_out: bool,
+    pub owed_blinds: OwedBlinds,
+    /// Chips the player bought over the session
+    pub bought_in: usize,
+    /// The player left and the seat is free for somebody else
+    pub left: bool,
 }
 
 impl Player {
@@ -15,10 +41,27 @@ impl Player {
         Self {
             name,
             hand: CardStack::new(0),
+            face_up: CardStack::new(0),
+            draws: vec![],
             eliminated: false,
             chips,
             folded: false,
             bet: 0,
+            total_bet: 0,
+            sitting_out: false,
+            owed_blinds: OwedBlinds::None,
+            bought_in: chips,
+            left: false,
         }
     }
+
+    /// Whether the player gets dealt into the next hand
+    pub fn is_playing(&self) -> bool {
+        !self.eliminated && !self.sitting_out && !self.left
+    }
+
+    /// Chips won or lost over the session so far
+    pub fn balance(&self) -> isize {
+        self.chips as isize - self.bought_in as isize
+    }
 }
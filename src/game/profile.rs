@@ -0,0 +1,356 @@
+use std::{fmt::Display, str::FromStr};
+
+use super::strategy::{HandClass, Strategy};
+
+/// Playing styles of the player pools bots imitate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// Plays few hands and rarely bets them
+    TightPassive,
+    /// Plays many hands and keeps betting them
+    LooseAggressive,
+    /// Calls with anything and almost never raises
+    CallingStation,
+    /// Raises with anything
+    Maniac,
+    /// Waits for the very best hands
+    Nit,
+}
+
+impl Style {
+    pub const ALL: [Style; 5] = [
+        Style::TightPassive,
+        Style::LooseAggressive,
+        Style::CallingStation,
+        Style::Maniac,
+        Style::Nit,
+    ];
+}
+
+impl FromStr for Style {
+    type Err = ();
+
+    fn from_str(style: &str) -> Result<Self, Self::Err> {
+        match style {
+            "tight-passive" => Ok(Style::TightPassive),
+            "loose-aggressive" => Ok(Style::LooseAggressive),
+            "calling-station" => Ok(Style::CallingStation),
+            "maniac" => Ok(Style::Maniac),
+            "nit" => Ok(Style::Nit),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for Style {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Style::TightPassive => "tight-passive",
+            Style::LooseAggressive => "loose-aggressive",
+            Style::CallingStation => "calling-station",
+            Style::Maniac => "maniac",
+            Style::Nit => "nit",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A named bot and how it plays, in the numbers a HUD shows for real players
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    pub name: String,
+    /// Share of hands the bot puts money in before the flop
+    pub vpip: f64,
+    /// Share of hands the bot raises before the flop
+    pub pfr: f64,
+    /// Bets and raises for every call
+    pub aggression: f64,
+    /// How often a weak hand bets when nobody did yet
+    pub bluff_frequency: f64,
+    /// Chance that losing a showdown makes the bot play looser for a while
+    pub tilt: f64,
+}
+
+impl Profile {
+    /// A solid regular, tight before the flop and aggressive after it
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            vpip: 0.2,
+            pfr: 0.15,
+            aggression: 2.0,
+            bluff_frequency: 0.1,
+            tilt: 0.0,
+        }
+    }
+
+    pub fn with_style(name: String, style: Style) -> Self {
+        let (vpip, pfr, aggression, bluff_frequency, tilt) = match style {
+            Style::TightPassive => (0.15, 0.04, 0.7, 0.03, 0.05),
+            Style::LooseAggressive => (0.35, 0.25, 3.0, 0.2, 0.1),
+            Style::CallingStation => (0.5, 0.05, 0.5, 0.02, 0.1),
+            Style::Maniac => (0.7, 0.45, 5.0, 0.4, 0.3),
+            Style::Nit => (0.1, 0.07, 1.5, 0.02, 0.0),
+        };
+
+        Self {
+            name,
+            vpip,
+            pfr,
+            aggression,
+            bluff_frequency,
+            tilt,
+        }
+    }
+
+    /// One bot of every style
+    pub fn lineup() -> Vec<Self> {
+        ["Alice", "Bob", "Charlie", "David", "Eve"]
+            .iter()
+            .zip(Style::ALL)
+            .map(|(name, style)| Self::with_style(name.to_string(), style))
+            .collect()
+    }
+
+    /// Rules that play close to the profile's numbers
+    pub fn strategy(&self) -> Strategy {
+        // late position plays the most hands, early position the fewest
+        let opening = [0.6, 1.0, 1.5, 1.2].map(|factor| weakest_class(self.vpip * factor));
+
+        Strategy {
+            opening,
+            raising: weakest_class(self.pfr),
+            value_threshold: (0.8 - 0.1 * self.aggression).clamp(0.3, 0.8),
+            raise_threshold: (0.95 - 0.1 * self.aggression).clamp(0.5, 0.95),
+            bet_size: (0.5 + 0.1 * self.aggression).min(1.5),
+            bluff_frequency: self.bluff_frequency.clamp(0.0, 1.0),
+            // hands played without raising them get called down
+            stickiness: (self.vpip - self.pfr).max(0.0) / 2.0,
+            tilt: self.tilt.clamp(0.0, 1.0),
+        }
+    }
+}
+
+/// Weakest hold'em hand to play for a share of `hands` played, by how often each class is dealt
+fn weakest_class(hands: f64) -> HandClass {
+    match hands {
+        _ if hands >= 0.4 => HandClass::Trash,
+        _ if hands >= 0.2 => HandClass::Speculative,
+        _ if hands >= 0.1 => HandClass::Playable,
+        _ if hands >= 0.05 => HandClass::Strong,
+        _ => HandClass::Premium,
+    }
+}
+
+/// Mistakes in a profile file, lines are counted from 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProfileError {
+    /// The file couldn't be read
+    Io(String),
+    /// Neither a `[name]` section nor a `key = value` inside one
+    Syntax {
+        line: usize,
+    },
+    UnknownStyle {
+        line: usize,
+        style: String,
+    },
+    UnknownKey {
+        line: usize,
+        key: String,
+    },
+    /// Values have to be finite numbers, shares of hands and chances between 0 and 1
+    InvalidValue {
+        line: usize,
+        key: String,
+    },
+}
+
+impl Display for ProfileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProfileError::Io(error) => write!(f, "can't read the profiles: {}", error),
+            ProfileError::Syntax { line } => write!(f, "line {} is not a section or setting", line),
+            ProfileError::UnknownStyle { line, style } => {
+                write!(f, "unknown style {} on line {}", style, line)
+            }
+            ProfileError::UnknownKey { line, key } => {
+                write!(f, "unknown setting {} on line {}", key, line)
+            }
+            ProfileError::InvalidValue { line, key } => write!(
+                f,
+                "{} on line {} has to be a number, between 0 and 1 unless it is the aggression",
+                key, line
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {}
+
+/// Reads bot profiles from a file, see `parse_profiles`
+pub fn load_profiles(path: &str) -> Result<Vec<Profile>, ProfileError> {
+    let text = std::fs::read_to_string(path).map_err(|e| ProfileError::Io(e.to_string()))?;
+    parse_profiles(&text)
+}
+
+/// Line, key and value of a setting
+type Setting<'a> = (usize, &'a str, &'a str);
+
+/// Reads one profile for every `[name]` section.
+/// `style = nit` starts from the numbers of a style, the other keys override them:
+/// `vpip`, `pfr`, `aggression`, `bluff_frequency` and `tilt`. Lines starting with `#` are comments
+pub fn parse_profiles(text: &str) -> Result<Vec<Profile>, ProfileError> {
+    // settings by section, the style goes first whatever line it is on
+    let mut sections: Vec<(String, Vec<Setting>)> = vec![];
+
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            sections.push((name.trim().to_string(), vec![]));
+            continue;
+        }
+
+        match (line.split_once('='), sections.last_mut()) {
+            (Some((key, value)), Some((_, settings))) => {
+                settings.push((line_number, key.trim(), value.trim()))
+            }
+            _ => return Err(ProfileError::Syntax { line: line_number }),
+        }
+    }
+
+    sections
+        .into_iter()
+        .map(|(name, mut settings)| {
+            settings.sort_by_key(|&(_, key, _)| key != "style");
+
+            let mut profile = Profile::new(name);
+            for (line, key, value) in settings {
+                if key == "style" {
+                    let style = value.parse().map_err(|_| ProfileError::UnknownStyle {
+                        line,
+                        style: value.to_string(),
+                    })?;
+                    profile = Profile::with_style(profile.name, style);
+                    continue;
+                }
+
+                let field = match key {
+                    "vpip" => &mut profile.vpip,
+                    "pfr" => &mut profile.pfr,
+                    "aggression" => &mut profile.aggression,
+                    "bluff_frequency" => &mut profile.bluff_frequency,
+                    "tilt" => &mut profile.tilt,
+                    _ => {
+                        return Err(ProfileError::UnknownKey {
+                            line,
+                            key: key.to_string(),
+                        })
+                    }
+                };
+
+                // the aggression is a ratio, everything else a share or a chance
+                let valid = |number: &f64| match key {
+                    "aggression" => number.is_finite() && *number >= 0.0,
+                    _ => (0.0..=1.0).contains(number),
+                };
+                let number = value.parse().ok().filter(valid);
+                *field = number.ok_or_else(|| ProfileError::InvalidValue {
+                    line,
+                    key: key.to_string(),
+                })?;
+            }
+
+            Ok(profile)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_profiles() {
+        let profiles = parse_profiles(
+            "# practice table\n\
+             [Alice]\n\
+             bluff_frequency = 0.5\n\
+             style = nit\n\
+             \n\
+             [Bob]\n\
+             vpip = 0.3\n",
+        )
+        .unwrap();
+
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].name, "Alice");
+        assert_eq!(profiles[0].vpip, 0.1);
+        assert_eq!(profiles[0].bluff_frequency, 0.5);
+        assert_eq!(profiles[1].vpip, 0.3);
+        assert_eq!(profiles[1].pfr, 0.15);
+
+        let table = parse_profiles(include_str!("../../bots.ini")).unwrap();
+        assert_eq!(table.len(), 5);
+        assert_eq!(table[3].tilt, 0.5);
+
+        assert_eq!(
+            parse_profiles("[Alice]\nshark = 1"),
+            Err(ProfileError::UnknownKey {
+                line: 2,
+                key: "shark".to_string()
+            })
+        );
+        assert_eq!(
+            parse_profiles("vpip = 0.3"),
+            Err(ProfileError::Syntax { line: 1 })
+        );
+        assert_eq!(
+            parse_profiles("[Alice]\nstyle = shark"),
+            Err(ProfileError::UnknownStyle {
+                line: 2,
+                style: "shark".to_string()
+            })
+        );
+        assert_eq!(
+            parse_profiles("[Alice]\nvpip = lots"),
+            Err(ProfileError::InvalidValue {
+                line: 2,
+                key: "vpip".to_string()
+            })
+        );
+        for (setting, key) in [
+            ("tilt = nan", "tilt"),
+            ("aggression = inf", "aggression"),
+            ("pfr = 1.5", "pfr"),
+            ("bluff_frequency = -0.1", "bluff_frequency"),
+            ("vpip = 2", "vpip"),
+        ] {
+            assert_eq!(
+                parse_profiles(&format!("[Alice]\n{}", setting)),
+                Err(ProfileError::InvalidValue {
+                    line: 2,
+                    key: key.to_string()
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_styles_play_differently() {
+        let nit = Profile::with_style("Nit".to_string(), Style::Nit).strategy();
+        let maniac = Profile::with_style("Maniac".to_string(), Style::Maniac).strategy();
+        let station = Profile::with_style("Station".to_string(), Style::CallingStation).strategy();
+
+        assert!(nit.opening.iter().zip(maniac.opening).all(|(&n, m)| n > m));
+        assert!(nit.raising > maniac.raising);
+        assert!(maniac.value_threshold < nit.value_threshold);
+        assert!(station.stickiness > nit.stickiness);
+        assert_eq!(maniac.opening[2], HandClass::Trash);
+    }
+}
@@ -0,0 +1,198 @@
+use std::sync::Arc;
+
+use super::{
+    variant::{BettingStructure, Variant},
+    variants::{DeuceToSeven, Holdem, Omaha, Razz, SevenCardStud, StudHiLo},
+};
+
+/// A game of a mixed rotation, played at its own stakes
+#[derive(Debug, Clone)]
+pub struct MixedGame {
+    pub variant: Arc<dyn Variant>,
+    /// Big blind, or the small bet in fixed limit games
+    pub blinds: usize,
+}
+
+impl MixedGame {
+    pub fn new(variant: Arc<dyn Variant>, blinds: usize) -> Self {
+        Self { variant, blinds }
+    }
+}
+
+/// How long a game of the rotation is played before the next one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Hands(usize),
+    /// Once around the table for everybody dealt in when the game starts
+    Orbits(usize),
+}
+
+/// Games a mixed table switches between
+#[derive(Debug, Clone)]
+pub struct Rotation {
+    pub games: Vec<MixedGame>,
+    pub period: Period,
+    /// The button picks every game instead of playing them in order
+    pub dealers_choice: bool,
+    /// Index into `games` of the game being played, `None` before the first hand
+    pub current: Option<usize>,
+    /// Hands left before the next switch
+    hands_left: usize,
+}
+
+impl Rotation {
+    /// Plays `games` in order, switching after every `period`
+    pub fn new(games: Vec<MixedGame>, period: Period) -> Self {
+        assert!(!games.is_empty(), "A rotation needs at least one game");
+
+        Self {
+            games,
+            period,
+            dealers_choice: false,
+            current: None,
+            hands_left: 0,
+        }
+    }
+
+    /// The player on the button picks one of `games` for every `period`
+    pub fn dealers_choice(games: Vec<MixedGame>, period: Period) -> Self {
+        Self {
+            dealers_choice: true,
+            ..Self::new(games, period)
+        }
+    }
+
+    /// Limit Hold'em, Omaha Hi-Lo, Razz, Stud and Stud Hi-Lo, an orbit each
+    pub fn horse(blinds: usize) -> Self {
+        let games: Vec<Arc<dyn Variant>> = vec![
+            Arc::new(Holdem::new(BettingStructure::FixedLimit)),
+            Arc::new(
+                Omaha::default()
+                    .hi_lo()
+                    .with_betting(BettingStructure::FixedLimit),
+            ),
+            Arc::new(Razz::default()),
+            Arc::new(SevenCardStud::default()),
+            Arc::new(StudHiLo::default()),
+        ];
+
+        Self::new(
+            games
+                .into_iter()
+                .map(|variant| MixedGame::new(variant, blinds))
+                .collect(),
+            Period::Orbits(1),
+        )
+    }
+
+    /// 2-7 Triple Draw and HORSE, then No Limit Hold'em and Pot Limit Omaha, eight hands each.
+    /// The big bet games play at half the stakes
+    pub fn eight_game(blinds: usize) -> Self {
+        let mut rotation = Self::horse(blinds);
+        rotation.games.insert(
+            0,
+            MixedGame::new(Arc::new(DeuceToSeven::triple_draw()), blinds),
+        );
+        rotation.games.extend([
+            MixedGame::new(Arc::new(Holdem::default()), blinds / 2),
+            MixedGame::new(Arc::new(Omaha::default()), blinds / 2),
+        ]);
+        rotation.period = Period::Hands(8);
+        rotation
+    }
+
+    /// The game being played, the first one before anything was played
+    pub fn game(&self) -> &MixedGame {
+        &self.games[self.current.unwrap_or(0)]
+    }
+
+    /// Whether the current game is over and the next hand starts a new one
+    pub fn is_due(&self) -> bool {
+        self.hands_left == 0
+    }
+
+    /// Game that comes after the current one when they are played in order
+    pub fn next_in_order(&self) -> usize {
+        self.current
+            .map_or(0, |current| (current + 1) % self.games.len())
+    }
+
+    /// Starts playing `games[game]` with `players` dealt in
+    pub fn switch(&mut self, game: usize, players: usize) -> &MixedGame {
+        self.current = Some(game);
+        self.hands_left = match self.period {
+            Period::Hands(hands) => hands,
+            Period::Orbits(orbits) => orbits * players,
+        }
+        .max(1);
+
+        &self.games[game]
+    }
+
+    pub fn hand_played(&mut self) {
+        self.hands_left = self.hands_left.saturating_sub(1);
+    }
+
+    /// Most players every game of the rotation can be dealt to
+    pub fn max_players(&self) -> usize {
+        self.games
+            .iter()
+            .map(|game| game.variant.max_players())
+            .min()
+            .unwrap_or(0)
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.games.iter().map(|game| game.variant.name()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::settings::GameType;
+
+    #[test]
+    fn test_rotation_order() {
+        let mut rotation = Rotation::horse(2000);
+        assert_eq!(rotation.max_players(), 8);
+        assert!(rotation.is_due());
+
+        let game = rotation.switch(rotation.next_in_order(), 3);
+        assert_eq!(game.variant.game_type(), GameType::TexasHoldem);
+
+        // an orbit of three players
+        for _ in 0..3 {
+            assert!(!rotation.is_due());
+            rotation.hand_played();
+        }
+        assert!(rotation.is_due());
+
+        let types: Vec<GameType> = (0..5)
+            .map(|_| {
+                let next = rotation.next_in_order();
+                rotation.switch(next, 3).variant.game_type()
+            })
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                GameType::OmahaHiLo,
+                GameType::Razz,
+                GameType::SevenCardStud,
+                GameType::StudHiLo,
+                GameType::TexasHoldem
+            ]
+        );
+    }
+
+    #[test]
+    fn test_eight_game() {
+        let rotation = Rotation::eight_game(2000);
+        assert_eq!(rotation.games.len(), 8);
+        assert_eq!(rotation.period, Period::Hands(8));
+        assert_eq!(rotation.max_players(), 6);
+        assert_eq!(rotation.games[0].variant.game_type(), GameType::TripleDraw);
+        assert_eq!(rotation.games[7].blinds, 1000);
+    }
+}
@@ -0,0 +1,438 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::{
+    actor::{Actionable, SyncActor},
+    hand::Event,
+    observer::Observer,
+    strategy::TablePosition,
+    table::Table,
+    variant::Variant,
+    variants::Holdem,
+};
+
+/// Makes a bot for a table of a match, given a seed for its random decisions
+pub type BotFactory = Arc<dyn Fn(u64) -> Box<dyn Actionable> + Send + Sync>;
+
+/// A bot of the line-up of a match
+#[derive(Clone)]
+pub struct Entrant {
+    pub name: String,
+    pub bot: BotFactory,
+}
+
+impl Entrant {
+    pub fn new(
+        name: &str,
+        bot: impl Fn(u64) -> Box<dyn Actionable> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            bot: Arc::new(bot),
+        }
+    }
+}
+
+/// Line-ups a match can't be played with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchError {
+    TooFewBots {
+        bots: usize,
+    },
+    /// More bots than the game has seats
+    TooManyBots {
+        bots: usize,
+        seats: usize,
+    },
+}
+
+impl Display for MatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatchError::TooFewBots { bots } => {
+                write!(f, "a match needs at least two bots, not {}", bots)
+            }
+            MatchError::TooManyBots { bots, seats } => {
+                write!(f, "{} bots don't fit on a table of {} seats", bots, seats)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MatchError {}
+
+/// Bots playing each other for a number of hands without anybody watching.
+/// Every stack is reset before every hand, so nobody busts and every hand counts the same
+pub struct Match {
+    pub entrants: Vec<Entrant>,
+    pub variant: Arc<dyn Variant>,
+    pub hands: usize,
+    /// Tables played at the same time, each on its own thread with its own seed
+    pub threads: usize,
+    pub seed: u64,
+    /// Big blind, or the small bet in fixed limit games
+    pub blinds: usize,
+    pub stack: usize,
+}
+
+impl Match {
+    /// No limit hold'em on a single table with stacks of 100 big blinds
+    pub fn new(entrants: Vec<Entrant>, hands: usize) -> Self {
+        Self {
+            entrants,
+            variant: Arc::new(Holdem::default()),
+            hands,
+            threads: 1,
+            seed: 0,
+            blinds: 2000,
+            stack: 200000,
+        }
+    }
+
+    pub fn with_threads(self, threads: usize) -> Self {
+        Self {
+            threads: threads.max(1),
+            ..self
+        }
+    }
+
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self { seed, ..self }
+    }
+
+    /// Plays every hand of the match, the same seed plays the same match
+    pub fn run(&self) -> Result<MatchReport, MatchError> {
+        let bots = self.entrants.len();
+        let seats = self.variant.max_players();
+        if bots < 2 {
+            return Err(MatchError::TooFewBots { bots });
+        }
+        if bots > seats {
+            return Err(MatchError::TooManyBots { bots, seats });
+        }
+
+        let mut seeds = StdRng::seed_from_u64(self.seed);
+        let tables: Vec<(u64, usize)> = (0..self.threads)
+            .map(|table| {
+                let hands =
+                    self.hands / self.threads + usize::from(table < self.hands % self.threads);
+                (seeds.gen(), hands)
+            })
+            .collect();
+
+        let results: Vec<Vec<Vec<(TablePosition, f64)>>> = thread::scope(|scope| {
+            let tables: Vec<_> = tables
+                .into_iter()
+                .map(|(seed, hands)| scope.spawn(move || self.play_table(seed, hands)))
+                .collect();
+
+            tables
+                .into_iter()
+                .map(|table| table.join().expect("A table of the match panicked"))
+                .collect()
+        });
+
+        let bots = self
+            .entrants
+            .iter()
+            .enumerate()
+            .map(|(seat, entrant)| {
+                let samples: Vec<(TablePosition, f64)> = results
+                    .iter()
+                    .flat_map(|table| table[seat].iter().copied())
+                    .collect();
+
+                let mut by_position: HashMap<TablePosition, Vec<f64>> = HashMap::new();
+                for &(position, won) in &samples {
+                    by_position.entry(position).or_default().push(won);
+                }
+
+                BotReport {
+                    name: entrant.name.clone(),
+                    overall: Winnings::from_samples(
+                        &samples.iter().map(|&(_, won)| won).collect::<Vec<_>>(),
+                    ),
+                    by_position: by_position
+                        .into_iter()
+                        .map(|(position, won)| (position, Winnings::from_samples(&won)))
+                        .collect(),
+                }
+            })
+            .collect();
+
+        Ok(MatchReport { bots })
+    }
+
+    /// Big blinds won in every hand by seat, with the position it was played from
+    fn play_table(&self, seed: u64, hands: usize) -> Vec<Vec<(TablePosition, f64)>> {
+        let mut seeds = StdRng::seed_from_u64(seed);
+        let mut table = Table::with_variant(
+            Arc::clone(&self.variant),
+            self.entrants.len(),
+            self.blinds,
+            self.stack,
+        );
+        table.quiet = true;
+        table.seed(seeds.gen());
+
+        let seats = Arc::new(SeatRecorder::default());
+        table.observe(Arc::clone(&seats) as Arc<dyn Observer>);
+        // without a time limit nothing has to wait for a bot on another thread
+        for entrant in &self.entrants {
+            let bot = SyncActor::inline(Arc::from((entrant.bot)(seeds.gen())));
+            table
+                .join_async(entrant.name.clone(), Arc::new(bot))
+                .expect("Every entrant has a seat");
+        }
+
+        let mut results = vec![Vec::with_capacity(hands); self.entrants.len()];
+        for _ in 0..hands {
+            for player in table.players.iter_mut() {
+                player.chips = self.stack;
+                player.eliminated = false;
+            }
+            let before: Vec<isize> = table.players.iter().map(|p| p.balance()).collect();
+
+            table.play_hand();
+
+            // the balance counts the chips of a bot that busted and bought back in
+            let positions = seats.positions();
+            for (seat, player) in table.players.iter().enumerate() {
+                let won = (player.balance() - before[seat]) as f64;
+                results[seat].push((positions[seat], won / self.blinds as f64));
+            }
+        }
+
+        results
+    }
+}
+
+/// Remembers where everybody sat in the last hand
+#[derive(Debug, Default)]
+struct SeatRecorder(Mutex<(usize, Vec<bool>, Vec<usize>)>);
+
+impl SeatRecorder {
+    fn positions(&self) -> Vec<TablePosition> {
+        let (button, dealt_in, blinds) = &*self.0.lock().unwrap();
+        (0..dealt_in.len())
+            .map(|seat| TablePosition::at(seat, *button, blinds, dealt_in))
+            .collect()
+    }
+}
+
+impl Observer for SeatRecorder {
+    fn notify(&self, event: &Event) {
+        let mut seats = self.0.lock().unwrap();
+        match event {
+            Event::HandStarted {
+                button, dealt_in, ..
+            } => *seats = (*button, dealt_in.clone(), vec![]),
+            Event::SmallBlind { seat, .. }
+            | Event::BigBlind { seat, .. }
+            | Event::BringIn { seat, .. } => seats.2.push(*seat),
+            _ => {}
+        }
+    }
+}
+
+/// Winnings in big blinds per 100 hands
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Winnings {
+    pub hands: usize,
+    pub bb_per_100: f64,
+    /// Infinite with less than two hands
+    pub standard_error: f64,
+}
+
+impl Winnings {
+    /// From the big blinds won in every hand
+    pub fn from_samples(samples: &[f64]) -> Self {
+        let hands = samples.len();
+        let mean = samples.iter().sum::<f64>() / hands.max(1) as f64;
+        let standard_error = match hands {
+            0 | 1 => f64::INFINITY,
+            _ => {
+                let variance = samples.iter().map(|won| (won - mean).powi(2)).sum::<f64>()
+                    / (hands - 1) as f64;
+                (variance / hands as f64).sqrt() * 100.0
+            }
+        };
+
+        Self {
+            hands,
+            bb_per_100: mean * 100.0,
+            standard_error,
+        }
+    }
+
+    /// Range that holds the true win rate with 95% confidence
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        let margin = 1.96 * self.standard_error;
+        (self.bb_per_100 - margin, self.bb_per_100 + margin)
+    }
+}
+
+impl Display for Winnings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (low, high) = self.confidence_interval();
+        write!(
+            f,
+            "{:.1} bb/100 ± {:.1}, 95% between {:.1} and {:.1}, {} hands",
+            self.bb_per_100, self.standard_error, low, high, self.hands
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BotReport {
+    pub name: String,
+    pub overall: Winnings,
+    pub by_position: HashMap<TablePosition, Winnings>,
+}
+
+/// Results of every bot of a match, in the order of the line-up
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchReport {
+    pub bots: Vec<BotReport>,
+}
+
+impl Display for MatchReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for bot in &self.bots {
+            writeln!(f, "{}: {}", bot.name, bot.overall)?;
+
+            for position in [
+                TablePosition::Early,
+                TablePosition::Middle,
+                TablePosition::Late,
+                TablePosition::Blinds,
+            ] {
+                if let Some(winnings) = bot.by_position.get(&position) {
+                    writeln!(f, "    {:?}: {}", position, winnings)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{game::actor::PlayerAction, game::context::GameContext, NPC};
+
+    fn lineup() -> Vec<Entrant> {
+        ["Alice", "Bob", "Charlie", "David"]
+            .map(|name| {
+                Entrant::new(name, move |seed| {
+                    Box::new(NPC::new(name.to_string()).with_seed(seed))
+                })
+            })
+            .to_vec()
+    }
+
+    #[test]
+    fn test_winnings() {
+        let winnings = Winnings::from_samples(&[1.0, -1.0, 3.0, -3.0]);
+        assert_eq!(winnings.bb_per_100, 0.0);
+        assert_eq!(
+            winnings.standard_error,
+            (20.0f64 / 3.0 / 4.0).sqrt() * 100.0
+        );
+        assert_eq!(
+            winnings.confidence_interval(),
+            (
+                -1.96 * winnings.standard_error,
+                1.96 * winnings.standard_error
+            )
+        );
+        assert!(Winnings::from_samples(&[2.0]).standard_error.is_infinite());
+    }
+
+    #[test]
+    fn test_match() {
+        let report = Match::new(lineup(), 41)
+            .with_threads(2)
+            .with_seed(9)
+            .run()
+            .unwrap();
+
+        assert_eq!(report.bots.len(), 4);
+        assert!(report.bots.iter().all(|bot| bot.overall.hands == 41));
+        assert!(report.bots.iter().all(|bot| bot
+            .by_position
+            .values()
+            .map(|w| w.hands)
+            .sum::<usize>()
+            == 41));
+
+        // chips only move between the bots
+        let total: f64 = report
+            .bots
+            .iter()
+            .map(|bot| bot.overall.bb_per_100 * bot.overall.hands as f64)
+            .sum();
+        assert!(total.abs() < 1e-6);
+
+        // the same seed plays the same match
+        let again = Match::new(lineup(), 41).with_threads(2).with_seed(9).run();
+        assert_eq!(Ok(report), again);
+    }
+
+    /// Goes all in every hand and buys back in whenever it busts
+    struct Shover;
+    impl Actionable for Shover {
+        fn action(&self, _game: &GameContext) -> PlayerAction {
+            PlayerAction::AllIn
+        }
+
+        fn wants_rebuy(&self, _buy_in: usize) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn test_rebuys_count_as_losses() {
+        let shovers = ["Alice", "Bob"]
+            .map(|name| Entrant::new(name, |_| Box::new(Shover)))
+            .to_vec();
+        let report = Match::new(shovers, 30).with_seed(4).run().unwrap();
+
+        // every hand somebody busts and buys back in, the stack they lost still counts
+        let total: f64 = report
+            .bots
+            .iter()
+            .map(|bot| bot.overall.bb_per_100 * bot.overall.hands as f64)
+            .sum();
+        assert!(total.abs() < 1e-6, "{}", total);
+        assert!(report
+            .bots
+            .iter()
+            .all(|bot| bot.overall.standard_error > 0.0));
+    }
+
+    #[test]
+    fn test_lineup_has_to_fit_the_table() {
+        let one = lineup().into_iter().take(1).collect();
+        assert_eq!(
+            Match::new(one, 10).run(),
+            Err(MatchError::TooFewBots { bots: 1 })
+        );
+
+        let crowd = (0..24).map(|_| lineup()[0].clone()).collect();
+        assert_eq!(
+            Match::new(crowd, 10).run(),
+            Err(MatchError::TooManyBots {
+                bots: 24,
+                seats: 23
+            })
+        );
+    }
+}
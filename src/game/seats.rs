@@ -0,0 +1,254 @@
+use std::fmt::Display;
+
+use super::settings::ButtonRule;
+
+/// Reasons the table turns down a request to join, leave or buy chips
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeatError {
+    TableFull,
+    /// Nobody sits on the seat
+    EmptySeat {
+        seat: usize,
+    },
+    /// Only busted players can rebuy
+    NotBusted {
+        seat: usize,
+    },
+    /// Busted players have to rebuy before anything else
+    Busted {
+        seat: usize,
+    },
+    /// Buying the chips would put the player over the table maximum
+    AboveMaximum {
+        maximum: usize,
+    },
+    /// The table does not offer add-ons
+    NoAddOn,
+}
+
+impl Display for SeatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SeatError::TableFull => write!(f, "the table is full"),
+            SeatError::EmptySeat { seat } => write!(f, "nobody sits on seat {}", seat),
+            SeatError::NotBusted { seat } => write!(f, "seat {} still has chips", seat),
+            SeatError::Busted { seat } => write!(f, "seat {} has to rebuy first", seat),
+            SeatError::AboveMaximum { maximum } => {
+                write!(f, "a stack can't be bigger than {}", maximum)
+            }
+            SeatError::NoAddOn => write!(f, "the table does not offer add-ons"),
+        }
+    }
+}
+
+impl std::error::Error for SeatError {}
+
+/// Seats of the button and the blinds for a single hand.
+///
+/// Under the dead button rule the button and the small blind can sit on a
+/// seat whose player has busted. The button is then dead and no small blind
+/// is posted for that hand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Positions {
+    pub button: usize,
+    pub small_blind: usize,
+    pub big_blind: usize,
+}
+
+/// Finds the next seat after `from` that is marked as live, wrapping around the table.
+/// `from` itself is only returned if it is the one live seat.
+pub fn next_seat(live: &[bool], from: usize) -> Option<usize> {
+    let len = live.len();
+    (1..=len)
+        .map(|offset| (from + offset) % len)
+        .find(|&seat| live[seat])
+}
+
+fn live_count(live: &[bool]) -> usize {
+    live.iter().filter(|&&l| l).count()
+}
+
+impl Positions {
+    /// Positions for the first hand, with the button on `button` or the next live seat after it.
+    pub fn first(live: &[bool], button: usize) -> Option<Self> {
+        let button = if live.get(button) == Some(&true) {
+            button
+        } else {
+            next_seat(live, button)?
+        };
+
+        Self::around(live, button)
+    }
+
+    /// Places the blinds left of a live button.
+    /// Heads-up the button posts the small blind.
+    fn around(live: &[bool], button: usize) -> Option<Self> {
+        if live_count(live) < 2 {
+            return None;
+        }
+
+        let small_blind = if live_count(live) == 2 {
+            button
+        } else {
+            next_seat(live, button)?
+        };
+        let big_blind = next_seat(live, small_blind)?;
+
+        Some(Self {
+            button,
+            small_blind,
+            big_blind,
+        })
+    }
+
+    /// Positions for the hand following this one.
+    pub fn next(&self, live: &[bool], rule: ButtonRule) -> Option<Self> {
+        if live_count(live) < 2 {
+            return None;
+        }
+
+        match rule {
+            ButtonRule::MovingButton => Self::around(live, next_seat(live, self.button)?),
+            ButtonRule::DeadButton => {
+                // the big blind always moves on to the next live player,
+                // the other seats follow the seats the blinds were on last hand
+                let big_blind = next_seat(live, self.big_blind)?;
+
+                if live_count(live) == 2 {
+                    // heads-up the other player takes the button and the small blind,
+                    // nobody pays the big blind twice in a row
+                    let button = next_seat(live, big_blind)?;
+                    return Some(Self {
+                        button,
+                        small_blind: button,
+                        big_blind,
+                    });
+                }
+
+                let small_blind = self.big_blind;
+                let button = self.small_blind;
+
+                if button == big_blind {
+                    // happens when a table grows out of heads-up play
+                    return self.next(live, ButtonRule::MovingButton);
+                }
+
+                Some(Self {
+                    button,
+                    small_blind,
+                    big_blind,
+                })
+            }
+        }
+    }
+
+    pub fn is_heads_up(&self) -> bool {
+        self.button == self.small_blind
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_seat_skips_busted_seats() {
+        let live = vec![true, false, false, true, true];
+
+        assert_eq!(next_seat(&live, 0), Some(3));
+        assert_eq!(next_seat(&live, 4), Some(0));
+        assert_eq!(next_seat(&live, 1), Some(3));
+        assert_eq!(next_seat(&[false, true], 1), Some(1));
+        assert_eq!(next_seat(&[false, false], 0), None);
+    }
+
+    #[test]
+    fn test_first_positions() {
+        let live = vec![true, true, true, true];
+        let positions = Positions::first(&live, 0).unwrap();
+
+        assert_eq!(positions.button, 0);
+        assert_eq!(positions.small_blind, 1);
+        assert_eq!(positions.big_blind, 2);
+
+        let live = vec![false, true, true, true];
+        let positions = Positions::first(&live, 0).unwrap();
+
+        assert_eq!(positions.button, 1);
+        assert_eq!(positions.small_blind, 2);
+        assert_eq!(positions.big_blind, 3);
+    }
+
+    #[test]
+    fn test_heads_up_button_posts_small_blind() {
+        let live = vec![true, false, true];
+        let positions = Positions::first(&live, 0).unwrap();
+
+        assert!(positions.is_heads_up());
+        assert_eq!(positions.small_blind, 0);
+        assert_eq!(positions.big_blind, 2);
+
+        let positions = positions.next(&live, ButtonRule::DeadButton).unwrap();
+        assert_eq!(positions.button, 2);
+        assert_eq!(positions.big_blind, 0);
+    }
+
+    #[test]
+    fn test_moving_button_skips_busted_players() {
+        let positions = Positions {
+            button: 0,
+            small_blind: 1,
+            big_blind: 2,
+        };
+        let live = vec![true, false, true, true, true];
+
+        let positions = positions.next(&live, ButtonRule::MovingButton).unwrap();
+        assert_eq!(positions.button, 2);
+        assert_eq!(positions.small_blind, 3);
+        assert_eq!(positions.big_blind, 4);
+    }
+
+    #[test]
+    fn test_dead_button() {
+        let positions = Positions {
+            button: 0,
+            small_blind: 1,
+            big_blind: 2,
+        };
+        // the big blind and the next player busted
+        let live = vec![true, true, false, false, true];
+
+        let positions = positions.next(&live, ButtonRule::DeadButton).unwrap();
+        assert_eq!(positions.big_blind, 4);
+        // dead small blind
+        assert_eq!(positions.small_blind, 2);
+        assert_eq!(positions.button, 1);
+
+        let positions = positions.next(&live, ButtonRule::DeadButton).unwrap();
+        assert_eq!(positions.big_blind, 0);
+        assert_eq!(positions.small_blind, 4);
+        // dead button
+        assert_eq!(positions.button, 2);
+
+        let positions = positions.next(&live, ButtonRule::DeadButton).unwrap();
+        assert_eq!(positions.big_blind, 1);
+        assert_eq!(positions.small_blind, 0);
+        assert_eq!(positions.button, 4);
+    }
+
+    #[test]
+    fn test_dead_button_going_heads_up() {
+        let positions = Positions {
+            button: 0,
+            small_blind: 1,
+            big_blind: 2,
+        };
+        let live = vec![true, false, true];
+
+        let positions = positions.next(&live, ButtonRule::DeadButton).unwrap();
+        // the big blind moves on, the former big blind gets the button
+        assert_eq!(positions.big_blind, 0);
+        assert_eq!(positions.button, 2);
+        assert_eq!(positions.small_blind, 2);
+    }
+}
@@ -1,8 +1,15 @@
-use crate::CardStack;
+use std::{sync::Arc, time::Duration};
 
-use super::player::Player;
+use super::{
+    rotation::{MixedGame, Period, Rotation},
+    variant::Variant,
+    variants::{
+        Badugi, Courchevel, CrazyPineapple, DeuceToSeven, FiveCardDraw, Holdem, Irish, Omaha,
+        Pineapple, Razz, SevenCardStud, StudHiLo,
+    },
+};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GameType {
     TexasHoldem, // 2 cards
     Omaha,       // 4 cards in hand afaik
@@ -10,6 +17,7 @@ pub enum GameType {
     // haha can we do all these games too?
     FiveCardDraw,
     SevenCardStud,
+    StudHiLo,
     FiveCardStud,
     Razz,
     Pineapple,
@@ -27,3 +35,84 @@ pub enum GameType {
     Horse,
     DealerChoice,
 }
+
+impl GameType {
+    /// Rules to play the game with, `None` for games the table can't run yet
+    pub fn variant(&self) -> Option<Arc<dyn Variant>> {
+        match self {
+            GameType::TexasHoldem => Some(Arc::new(Holdem::default())),
+            GameType::Omaha => Some(Arc::new(Omaha::default())),
+            GameType::OmahaHiLo => Some(Arc::new(Omaha::default().hi_lo())),
+            GameType::DoubleBoardOmaha => Some(Arc::new(Omaha::default().double_board())),
+            GameType::FiveCardDraw => Some(Arc::new(FiveCardDraw::default())),
+            GameType::SevenCardStud => Some(Arc::new(SevenCardStud::default())),
+            GameType::StudHiLo => Some(Arc::new(StudHiLo::default())),
+            GameType::Razz => Some(Arc::new(Razz::default())),
+            GameType::Pineapple => Some(Arc::new(Pineapple::default())),
+            GameType::CrazyPineapple => Some(Arc::new(CrazyPineapple::default())),
+            GameType::Courchevel => Some(Arc::new(Courchevel::default())),
+            GameType::Irish => Some(Arc::new(Irish::default())),
+            GameType::TripleDraw => Some(Arc::new(DeuceToSeven::triple_draw())),
+            GameType::SingleDraw => Some(Arc::new(DeuceToSeven::single_draw())),
+            GameType::Badugi => Some(Arc::new(Badugi::default())),
+            _ => None,
+        }
+    }
+
+    /// Games a mixed game switches between, `None` for single games.
+    /// Dealer's choice picks from the 8-Game lineup and Badugi every orbit
+    pub fn rotation(&self, blinds: usize) -> Option<Rotation> {
+        match self {
+            GameType::Horse => Some(Rotation::horse(blinds)),
+            GameType::EightGame => Some(Rotation::eight_game(blinds)),
+            GameType::DealerChoice => {
+                let mut games = Rotation::eight_game(blinds).games;
+                games.push(MixedGame::new(Arc::new(Badugi::default()), blinds));
+                Some(Rotation::dealers_choice(games, Period::Orbits(1)))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// How the button moves when players are eliminated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ButtonRule {
+    /// The big blind always moves to the next live player, the button and the
+    /// small blind may end up on an empty seat.
+    #[default]
+    DeadButton,
+    /// The button always moves to the next live player, the blinds follow it.
+    MovingButton,
+}
+
+/// How long players get to make a decision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeLimit {
+    /// Time for every decision
+    pub decision: Duration,
+    /// Extra time a player can use up over the session
+    pub time_bank: Duration,
+    /// When a used up time bank is filled again
+    pub refill: TimeBankRefill,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeBankRefill {
+    Never,
+    /// At the start of every hand
+    EveryHand,
+    /// Every given number of hands, like a blind level
+    EveryHands(usize),
+}
+
+impl TimeLimit {
+    /// Whether the time banks are filled up before hand number `hand`, counting from zero
+    pub fn refills_before(&self, hand: usize) -> bool {
+        match self.refill {
+            TimeBankRefill::Never => false,
+            TimeBankRefill::EveryHand => true,
+            TimeBankRefill::EveryHands(hands) => hand.is_multiple_of(hands),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use futures_signals::signal::Mutable;
+
+use crate::CardStack;
+
+use super::hand::HandState;
+
+/// Table state as signals so UIs can react to changes instead of polling.
+/// Only holds what everybody at the table gets to see.
+#[derive(Debug, Clone, Default)]
+pub struct TableSignals {
+    /// Name of the game being played
+    pub game: Mutable<String>,
+    pub street: Mutable<&'static str>,
+    pub pot: Mutable<usize>,
+    pub current_bet: Mutable<usize>,
+    /// Bets of the current betting round by seat
+    pub bets: Mutable<Vec<usize>>,
+    pub chips: Mutable<Vec<usize>>,
+    /// Community cards, one stack per board
+    pub boards: Mutable<Vec<CardStack>>,
+    /// Cards dealt face up by seat
+    pub face_up: Mutable<Vec<CardStack>>,
+    pub to_act: Mutable<Option<usize>>,
+    pub hands_played: Mutable<usize>,
+}
+
+impl TableSignals {
+    /// Publishes the state of the hand, only values that changed notify their signals
+    pub fn update(&self, state: &HandState) {
+        self.street.set_neq(state.street_name());
+        self.pot.set_neq(state.pot());
+        self.current_bet.set_neq(state.current_bet());
+        self.bets
+            .set_neq(state.players.iter().map(|p| p.bet).collect());
+        self.chips
+            .set_neq(state.players.iter().map(|p| p.chips).collect());
+        self.boards.set_neq(state.boards.clone());
+        self.face_up
+            .set_neq(state.players.iter().map(|p| p.face_up.clone()).collect());
+        self.to_act.set_neq(state.to_act);
+    }
+}
@@ -0,0 +1,368 @@
+use std::sync::Mutex;
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use crate::{util::executor::is_cancelled, Card, CardStack, CONCEALED};
+
+use super::{
+    actor::{heuristic_discard, Actionable, Discard, PlayerAction},
+    context::GameContext,
+    hand::Event,
+    strategy::{raise_to, strength, HandClass},
+    validation::IllegalActionPolicy,
+    variant::Evaluator,
+};
+
+/// Bot that deals the rest of the hand out many times to estimate its equity
+pub struct MonteCarlo {
+    name: String,
+    /// Rollouts per decision
+    simulations: usize,
+    /// Opponents that bet or raised get dealt strong hands more often
+    weighted: bool,
+    /// Share of the effective stack the bot expects to win later when it calls with cards to come
+    implied_odds: f64,
+    /// Equity needed to bet or raise
+    raise_equity: f64,
+    rng: Mutex<StdRng>,
+}
+
+impl MonteCarlo {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            simulations: 500,
+            weighted: false,
+            implied_odds: 0.1,
+            raise_equity: 0.6,
+            rng: Mutex::new(StdRng::from_entropy()),
+        }
+    }
+
+    pub fn with_simulations(self, simulations: usize) -> Self {
+        Self {
+            simulations: simulations.max(1),
+            ..self
+        }
+    }
+
+    /// Share of the effective stack to count on winning later when calling with cards to come
+    pub fn with_implied_odds(self, implied_odds: f64) -> Self {
+        Self {
+            implied_odds: implied_odds.max(0.0),
+            ..self
+        }
+    }
+
+    /// Equity at which the bot stops calling and bets or raises
+    pub fn with_raise_equity(self, raise_equity: f64) -> Self {
+        Self {
+            raise_equity: raise_equity.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    /// Weighs the hands dealt to opponents by how they played so far
+    pub fn weighted(self) -> Self {
+        Self {
+            weighted: true,
+            ..self
+        }
+    }
+
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self {
+            rng: Mutex::new(StdRng::seed_from_u64(seed)),
+            ..self
+        }
+    }
+}
+
+impl Actionable for MonteCarlo {
+    fn action(&self, game: &GameContext) -> PlayerAction {
+        let mut rng = self.rng.lock().unwrap();
+        let equity = match self.weighted {
+            true => equity(game, self.simulations, aggression_weight(game), &mut *rng),
+            false => equity(game, self.simulations, |_, _, _| 1.0, &mut *rng),
+        };
+
+        let call = game.call_amount;
+        let pot = game.pot + call;
+        let implied = match game.community_to_come + game.hole_to_come {
+            0 => 0.0,
+            _ => game.effective_stack.saturating_sub(call) as f64 * self.implied_odds,
+        };
+
+        if equity >= self.raise_equity {
+            raise_to(game, game.current_bet + (pot as f64 * equity) as usize)
+        } else if call == 0 {
+            PlayerAction::Check
+        } else if equity * (pot as f64 + implied) >= call as f64 {
+            PlayerAction::Call
+        } else {
+            PlayerAction::Fold
+        }
+    }
+
+    fn discard(&self, game: &GameContext) -> Discard {
+        heuristic_discard(game)
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn illegal_action_policy(&self) -> IllegalActionPolicy {
+        IllegalActionPolicy::Call
+    }
+
+    fn choose_game(&self, games: &[String]) -> usize {
+        match games.len() {
+            0 => 0,
+            len => self.rng.lock().unwrap().gen_range(0..len),
+        }
+    }
+}
+
+/// How likely the players that bet or raised this hand hold `cards`, given the `board` they saw
+fn aggression_weight(game: &GameContext) -> impl Fn(usize, &[Card], &[Card]) -> f64 {
+    let aggressors: Vec<usize> = game
+        .history
+        .iter()
+        .filter_map(|event| match event {
+            Event::Bet { seat, .. } | Event::Raised { seat, .. } | Event::AllIn { seat } => {
+                Some(*seat)
+            }
+            _ => None,
+        })
+        .collect();
+    let evaluator = game.evaluator;
+
+    move |seat, cards, board| {
+        if !aggressors.contains(&seat) {
+            return 1.0;
+        }
+
+        match (evaluator, cards, board.is_empty()) {
+            (Evaluator::High, &[first, second], true) => match HandClass::of(first, second) {
+                HandClass::Premium | HandClass::Strong => 1.0,
+                HandClass::Playable => 0.6,
+                HandClass::Speculative => 0.3,
+                HandClass::Trash => 0.1,
+            },
+            _ => (strength(evaluator, cards, board) + 0.2).min(1.0),
+        }
+    }
+}
+
+/// Share of the pot the player on `game.seat` wins on average over `simulations` rollouts.
+/// `weight` says how likely an opponent on a seat holds the sampled cards with the current board,
+/// from 0 to 1, unlikely hands get dealt again a few times
+pub fn equity(
+    game: &GameContext,
+    simulations: usize,
+    weight: impl Fn(usize, &[Card], &[Card]) -> f64,
+    rng: &mut impl Rng,
+) -> f64 {
+    let opponents: Vec<usize> = (0..game.players.len())
+        .filter(|&seat| seat != game.seat && !game.players[seat].folded)
+        .collect();
+    if opponents.is_empty() {
+        return 1.0;
+    }
+
+    let known: Vec<Card> = game
+        .hand
+        .cards
+        .iter()
+        .chain(game.boards.iter().flat_map(|board| board.cards.iter()))
+        .chain(game.players.iter().flat_map(|p| p.hand.cards.iter()))
+        .copied()
+        .filter(|&card| card != CONCEALED)
+        .collect();
+    let unknown: Vec<Card> = CardStack::standard_deck()
+        .cards
+        .into_iter()
+        .filter(|card| !known.contains(card))
+        .collect();
+
+    let first_board = game.boards.first().map_or(&[][..], |b| &b.cards[..]);
+    let mut won = 0.0;
+    let mut rollouts = 0;
+
+    // a decision the table stopped waiting for ends with the rollouts made so far
+    while rollouts < simulations && (rollouts == 0 || !is_cancelled()) {
+        rollouts += 1;
+        let mut deck = unknown.clone();
+        deck.shuffle(rng);
+
+        let mut hands: Vec<Vec<Card>> = vec![];
+        for &seat in &opponents {
+            let seen = &game.players[seat].hand.cards;
+            let concealed = seen.iter().filter(|&&c| c == CONCEALED).count();
+
+            let mut hand = vec![];
+            for attempt in 0..5 {
+                let drawn = draw(&mut deck, concealed);
+                hand = seen
+                    .iter()
+                    .copied()
+                    .filter(|&c| c != CONCEALED)
+                    .chain(drawn.iter().copied())
+                    .collect();
+
+                if attempt == 4 || rng.gen::<f64>() < weight(seat, &hand, first_board) {
+                    break;
+                }
+
+                // the front of the deck is dealt last
+                deck.splice(0..0, drawn);
+            }
+
+            hand.extend(draw(&mut deck, game.hole_to_come));
+            hands.push(hand);
+        }
+
+        let mut own = game.hand.cards.clone();
+        own.extend(draw(&mut deck, game.hole_to_come));
+        let boards: Vec<Vec<Card>> = game
+            .boards
+            .iter()
+            .map(|board| {
+                let mut cards = board.cards.clone();
+                cards.extend(draw(&mut deck, game.community_to_come));
+                cards
+            })
+            .collect();
+
+        won += boards
+            .iter()
+            .map(|board| share(game.evaluator, &own, &hands, board))
+            .sum::<f64>()
+            / boards.len().max(1) as f64;
+    }
+
+    won / rollouts as f64
+}
+
+/// Up to `count` cards off the back of `deck`
+fn draw(deck: &mut Vec<Card>, count: usize) -> Vec<Card> {
+    deck.split_off(deck.len().saturating_sub(count))
+}
+
+/// Part of the pot `own` wins against `hands` at showdown on `board`
+fn share(evaluator: Evaluator, own: &[Card], hands: &[Vec<Card>], board: &[Card]) -> f64 {
+    let own = evaluator.evaluate(own, board);
+    let others: Vec<_> = hands
+        .iter()
+        .map(|hand| evaluator.evaluate(hand, board))
+        .collect();
+
+    let split = |own: u32, others: &[u32]| -> f64 {
+        let best = others.iter().copied().max().unwrap_or(0);
+        match own.cmp(&best) {
+            std::cmp::Ordering::Greater => 1.0,
+            std::cmp::Ordering::Equal => {
+                1.0 / (1 + others.iter().filter(|&&r| r == best).count()) as f64
+            }
+            std::cmp::Ordering::Less => 0.0,
+        }
+    };
+
+    let high = split(
+        own.rank.0,
+        &others.iter().map(|v| v.rank.0).collect::<Vec<_>>(),
+    );
+
+    // without a qualifying low the high hand takes the whole pot
+    let lows: Vec<u32> = others.iter().filter_map(|v| v.low).map(|r| r.0).collect();
+    match (own.low, lows.is_empty()) {
+        (None, true) => high,
+        (None, false) => high / 2.0,
+        (Some(low), _) => (high + split(low.0, &lows)) / 2.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::game::{
+        hand::{apply, new_game, Action, HandState},
+        variants::Holdem,
+    };
+    use crate::*;
+
+    /// Heads up hold'em after the deal with the button holding `cards`
+    fn dealt_hand(cards: [Card; 2]) -> HandState {
+        let state = new_game(Arc::new(Holdem::default()), &[20000; 2]);
+        let (state, _) = apply(&state, Action::PostBlinds).unwrap();
+        let (mut state, _) = apply(&state, Action::Deal).unwrap();
+        state.players[0].hand = CardStack::from(cards.to_vec());
+        state
+    }
+
+    #[test]
+    fn test_equity() {
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let state = dealt_hand([ACE | SPADE, ACE | HEART]);
+        let aces = equity(&GameContext::new(&state, 0), 2000, |_, _, _| 1.0, &mut rng);
+        assert!((0.8..0.9).contains(&aces), "{}", aces);
+
+        let state = dealt_hand([SEVEN | SPADE, TWO | HEART]);
+        let seven_deuce = equity(&GameContext::new(&state, 0), 2000, |_, _, _| 1.0, &mut rng);
+        assert!((0.28..0.4).contains(&seven_deuce), "{}", seven_deuce);
+
+        // a royal flush on the river can't lose
+        let mut state = dealt_hand([ACE | SPADE, KING | SPADE]);
+        state.boards[0] = CardStack::from(vec![
+            QUEEN | SPADE,
+            JACK | SPADE,
+            TEN | SPADE,
+            TWO | HEART,
+            THREE | CLUB,
+        ]);
+        let mut context = GameContext::new(&state, 0);
+        context.community_to_come = 0;
+        assert_eq!(equity(&context, 100, |_, _, _| 1.0, &mut rng), 1.0);
+    }
+
+    #[test]
+    fn test_monte_carlo_decisions() {
+        let bot = MonteCarlo::new("Carlo".to_string())
+            .with_simulations(300)
+            .weighted()
+            .with_seed(3);
+
+        let state = dealt_hand([ACE | SPADE, ACE | HEART]);
+        assert!(matches!(
+            bot.action(&GameContext::new(&state, 0)),
+            PlayerAction::RaiseTo(_) | PlayerAction::AllIn
+        ));
+
+        // seven deuce can't call a shove
+        let state = dealt_hand([SEVEN | SPADE, TWO | HEART]);
+        let (state, _) = apply(&state, Action::Act(0, PlayerAction::Call)).unwrap();
+        let (mut state, _) = apply(&state, Action::Act(1, PlayerAction::AllIn)).unwrap();
+        state.players[0].hand = CardStack::from(vec![SEVEN | SPADE, TWO | HEART]);
+        assert_eq!(bot.action(&GameContext::new(&state, 0)), PlayerAction::Fold);
+
+        // a raise with stacks behind gets called by a bot counting on winning them when it hits
+        let dreamer = MonteCarlo::new("Dreamer".to_string())
+            .with_simulations(300)
+            .with_implied_odds(1.0)
+            .with_raise_equity(1.0)
+            .with_seed(3);
+        let state = dealt_hand([SEVEN | SPADE, TWO | HEART]);
+        let (state, _) = apply(&state, Action::Act(0, PlayerAction::Call)).unwrap();
+        let (mut state, _) = apply(&state, Action::Act(1, PlayerAction::RaiseTo(6000))).unwrap();
+        state.players[0].hand = CardStack::from(vec![SEVEN | SPADE, TWO | HEART]);
+        assert_eq!(
+            dreamer.action(&GameContext::new(&state, 0)),
+            PlayerAction::Call
+        );
+        assert_eq!(dreamer.choose_game(&[]), 0);
+    }
+}
@@ -0,0 +1,560 @@
+use std::{collections::HashMap, fmt::Display, sync::Mutex};
+
+use super::{hand::Event, observer::Observer, strategy::TablePosition};
+
+/// How often a player did something out of the times they could, `opportunities` is the sample size
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stat {
+    pub count: usize,
+    pub opportunities: usize,
+}
+
+impl Stat {
+    fn record(&mut self, done: bool) {
+        self.opportunities += 1;
+        self.count += usize::from(done);
+    }
+
+    /// Share of the opportunities taken, `None` without any
+    pub fn ratio(&self) -> Option<f64> {
+        (self.opportunities > 0).then(|| self.count as f64 / self.opportunities as f64)
+    }
+}
+
+/// First betting round stats of a player from one position
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PositionStats {
+    pub vpip: Stat,
+    pub pfr: Stat,
+}
+
+/// What a HUD shows about a player.
+/// Preflop means the first betting round of any game: third street in stud, before the first draw in draw games.
+/// The flop is the betting round after it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlayerStats {
+    /// Hands dealt
+    pub hands: usize,
+    /// Put money in before the flop without being forced to
+    pub vpip: Stat,
+    /// Raised before the flop
+    pub pfr: Stat,
+    /// Reraised a single raise before the flop
+    pub three_bet: Stat,
+    /// Folded a raise before the flop to a reraise
+    pub fold_to_three_bet: Stat,
+    /// Bet the flop after raising last before it
+    pub continuation_bet: Stat,
+    pub fold_to_continuation_bet: Stat,
+    /// Went to showdown out of the hands that saw the flop
+    pub went_to_showdown: Stat,
+    /// Won chips out of the showdowns
+    pub won_at_showdown: Stat,
+    /// Bets and raises on every street
+    pub aggressive_actions: usize,
+    pub calls: usize,
+    pub by_position: HashMap<TablePosition, PositionStats>,
+}
+
+impl PlayerStats {
+    /// Bets and raises for every call, `None` without any calls
+    pub fn aggression_factor(&self) -> Option<f64> {
+        (self.calls > 0).then(|| self.aggressive_actions as f64 / self.calls as f64)
+    }
+}
+
+impl Display for PlayerStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let percent = |stat: Stat| match stat.ratio() {
+            Some(ratio) => format!("{:.0}%", ratio * 100.0),
+            None => "-".to_string(),
+        };
+        let aggression = match self.aggression_factor() {
+            Some(factor) => format!("{:.1}", factor),
+            None => "-".to_string(),
+        };
+
+        write!(
+            f,
+            "VPIP {} PFR {} 3-bet {} AF {} WTSD {}, {} hands",
+            percent(self.vpip),
+            percent(self.pfr),
+            percent(self.three_bet),
+            aggression,
+            percent(self.went_to_showdown),
+            self.hands
+        )
+    }
+}
+
+/// Who did what in the hand being played, by seat
+#[derive(Debug, Default)]
+struct CurrentHand {
+    names: Vec<String>,
+    button: usize,
+    dealt_in: Vec<bool>,
+    blinds: Vec<usize>,
+    /// Betting rounds started so far, see `FIRST_ROUND`
+    rounds: usize,
+    /// Bets and raises on the current street, blinds and bring-ins don't count
+    raises: usize,
+    vpip: Vec<bool>,
+    pfr: Vec<bool>,
+    preflop_recorded: bool,
+    /// First to raise before the flop
+    opener: Option<usize>,
+    /// Last to raise before the flop
+    aggressor: Option<usize>,
+    /// The opener answered the reraise already
+    answered_three_bet: bool,
+    continuation_bet: Option<bool>,
+    /// Players that answered the continuation bet already
+    answered_continuation_bet: Vec<bool>,
+    folded: Vec<bool>,
+    saw_second_round: Vec<bool>,
+    showed: Vec<bool>,
+    won: Vec<bool>,
+}
+
+impl CurrentHand {
+    fn new(names: &[String], button: usize, dealt_in: &[bool]) -> Self {
+        let seats = names.len();
+        Self {
+            names: names.to_vec(),
+            button,
+            dealt_in: dealt_in.to_vec(),
+            vpip: vec![false; seats],
+            pfr: vec![false; seats],
+            answered_continuation_bet: vec![false; seats],
+            folded: dealt_in.iter().map(|&dealt| !dealt).collect(),
+            saw_second_round: vec![false; seats],
+            showed: vec![false; seats],
+            won: vec![false; seats],
+            ..Self::default()
+        }
+    }
+}
+
+/// Betting round the preflop stats come from: preflop, third street or the round before the first draw
+const FIRST_ROUND: usize = 1;
+/// Betting round continuation bets and showdowns count from: the flop, fourth street or the first draw
+const SECOND_ROUND: usize = 2;
+
+/// Stats of every player seen from the actions at the table
+#[derive(Debug, Default)]
+struct Tracker {
+    players: HashMap<String, PlayerStats>,
+    hand: CurrentHand,
+}
+
+impl Tracker {
+    fn player(&mut self, seat: usize) -> &mut PlayerStats {
+        let name = self.hand.names[seat].clone();
+        self.players.entry(name).or_default()
+    }
+
+    fn record(&mut self, event: &Event) {
+        match event {
+            Event::HandStarted {
+                players,
+                button,
+                dealt_in,
+                ..
+            } => {
+                self.hand = CurrentHand::new(players, *button, dealt_in);
+                for seat in (0..players.len()).filter(|&seat| dealt_in[seat]) {
+                    self.player(seat).hands += 1;
+                }
+            }
+            // the bring-in is the forced bet of stud, it plays from the blinds
+            Event::SmallBlind { seat, .. }
+            | Event::BigBlind { seat, .. }
+            | Event::BringIn { seat, .. } => self.hand.blinds.push(*seat),
+            Event::StreetStarted { .. } => {
+                self.hand.rounds += 1;
+                self.hand.raises = 0;
+
+                if self.hand.rounds == SECOND_ROUND {
+                    self.finish_preflop();
+
+                    for seat in 0..self.hand.names.len() {
+                        if !self.hand.folded[seat] {
+                            self.hand.saw_second_round[seat] = true;
+                            self.player(seat).went_to_showdown.opportunities += 1;
+                        }
+                    }
+                }
+            }
+            Event::Checked { seat } => self.act(*seat, Act::Check),
+            Event::Called { seat, .. } => self.act(*seat, Act::Call),
+            Event::Bet { seat, .. } | Event::Raised { seat, .. } => self.act(*seat, Act::Raise),
+            Event::Folded { seat } => self.act(*seat, Act::Fold),
+            Event::Shown { seat, .. } => {
+                let seat = *seat;
+                self.hand.showed[seat] = true;
+                let saw_second_round = self.hand.saw_second_round[seat];
+
+                let player = self.player(seat);
+                player.won_at_showdown.opportunities += 1;
+                if saw_second_round {
+                    player.went_to_showdown.count += 1;
+                }
+            }
+            Event::PotWon { seat, .. } => {
+                self.finish_preflop();
+
+                let seat = *seat;
+                if self.hand.showed[seat] && !self.hand.won[seat] {
+                    self.hand.won[seat] = true;
+                    self.player(seat).won_at_showdown.count += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Counts the preflop stats once nobody can act in the first betting round anymore
+    fn finish_preflop(&mut self) {
+        if self.hand.preflop_recorded || self.hand.rounds < FIRST_ROUND {
+            return;
+        }
+        self.hand.preflop_recorded = true;
+
+        let hand = &self.hand;
+        let results: Vec<(usize, bool, bool, TablePosition)> = (0..hand.names.len())
+            .filter(|&seat| hand.dealt_in[seat])
+            .map(|seat| {
+                let position = TablePosition::at(seat, hand.button, &hand.blinds, &hand.dealt_in);
+                (seat, hand.vpip[seat], hand.pfr[seat], position)
+            })
+            .collect();
+
+        for (seat, vpip, pfr, position) in results {
+            let player = self.player(seat);
+            player.vpip.record(vpip);
+            player.pfr.record(pfr);
+
+            let at_position = player.by_position.entry(position).or_default();
+            at_position.vpip.record(vpip);
+            at_position.pfr.record(pfr);
+        }
+    }
+
+    fn act(&mut self, seat: usize, act: Act) {
+        let hand = &mut self.hand;
+        let raised = act == Act::Raise;
+        let folded = act == Act::Fold;
+
+        let mut three_bet = None;
+        let mut fold_to_three_bet = None;
+        let mut continuation_bet = None;
+        let mut fold_to_continuation_bet = None;
+
+        match hand.rounds {
+            FIRST_ROUND => {
+                if hand.raises == 1 && hand.opener != Some(seat) {
+                    three_bet = Some(raised);
+                }
+                if hand.raises == 2 && hand.opener == Some(seat) && !hand.answered_three_bet {
+                    hand.answered_three_bet = true;
+                    fold_to_three_bet = Some(folded);
+                }
+
+                hand.vpip[seat] |= act == Act::Call || raised;
+                hand.pfr[seat] |= raised;
+                if raised {
+                    hand.opener = hand.opener.or(Some(seat));
+                    hand.aggressor = Some(seat);
+                }
+            }
+            SECOND_ROUND => {
+                if hand.aggressor == Some(seat) && hand.continuation_bet.is_none() {
+                    if hand.raises == 0 {
+                        continuation_bet = Some(raised);
+                    }
+                    hand.continuation_bet = Some(raised && hand.raises == 0);
+                }
+                if hand.continuation_bet == Some(true)
+                    && hand.raises == 1
+                    && hand.aggressor != Some(seat)
+                    && !hand.answered_continuation_bet[seat]
+                {
+                    hand.answered_continuation_bet[seat] = true;
+                    fold_to_continuation_bet = Some(folded);
+                }
+            }
+            _ => {}
+        }
+
+        if raised {
+            hand.raises += 1;
+        }
+        if folded {
+            hand.folded[seat] = true;
+        }
+
+        let player = self.player(seat);
+        match act {
+            Act::Raise => player.aggressive_actions += 1,
+            Act::Call => player.calls += 1,
+            Act::Check | Act::Fold => {}
+        }
+        if let Some(done) = three_bet {
+            player.three_bet.record(done);
+        }
+        if let Some(done) = fold_to_three_bet {
+            player.fold_to_three_bet.record(done);
+        }
+        if let Some(done) = continuation_bet {
+            player.continuation_bet.record(done);
+        }
+        if let Some(done) = fold_to_continuation_bet {
+            player.fold_to_continuation_bet.record(done);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Act {
+    Check,
+    Call,
+    /// Bets count as raises
+    Raise,
+    Fold,
+}
+
+/// Keeps stats of everybody at the table from the public actions, by player name.
+/// Share it with an `Arc` to query it while the table runs
+#[derive(Debug, Default)]
+pub struct Hud(Mutex<Tracker>);
+
+impl Hud {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn stats(&self, name: &str) -> Option<PlayerStats> {
+        self.0.lock().unwrap().players.get(name).cloned()
+    }
+
+    /// Stats of everybody that was dealt a hand so far
+    pub fn all(&self) -> HashMap<String, PlayerStats> {
+        self.0.lock().unwrap().players.clone()
+    }
+}
+
+impl Observer for Hud {
+    fn notify(&self, event: &Event) {
+        self.0.lock().unwrap().record(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CardStack;
+
+    fn started() -> Event {
+        Event::HandStarted {
+            game: "Texas Hold'em".to_string(),
+            button: 0,
+            players: ["Alice", "Bob", "Charlie"].map(String::from).to_vec(),
+            dealt_in: vec![true; 3],
+        }
+    }
+
+    fn play(hud: &Hud, events: &[Event]) {
+        events.iter().for_each(|event| hud.notify(event));
+    }
+
+    #[test]
+    fn test_preflop_stats() {
+        let hud = Hud::new();
+        play(
+            &hud,
+            &[
+                started(),
+                Event::SmallBlind {
+                    seat: 1,
+                    amount: 1000,
+                },
+                Event::BigBlind {
+                    seat: 2,
+                    amount: 2000,
+                },
+                Event::StreetStarted { street: "Preflop" },
+                Event::Raised { seat: 0, to: 6000 },
+                Event::Raised { seat: 1, to: 18000 },
+                Event::Folded { seat: 2 },
+                Event::Folded { seat: 0 },
+                Event::PotWon {
+                    seat: 1,
+                    amount: 26000,
+                    hand: None,
+                },
+            ],
+        );
+
+        let alice = hud.stats("Alice").unwrap();
+        assert_eq!(alice.hands, 1);
+        assert_eq!(alice.pfr.ratio(), Some(1.0));
+        assert_eq!(alice.fold_to_three_bet.ratio(), Some(1.0));
+        assert_eq!(alice.by_position[&TablePosition::Late].vpip.count, 1);
+
+        let bob = hud.stats("Bob").unwrap();
+        assert_eq!(
+            bob.three_bet,
+            Stat {
+                count: 1,
+                opportunities: 1
+            }
+        );
+        assert_eq!(bob.by_position[&TablePosition::Blinds].pfr.count, 1);
+
+        let charlie = hud.stats("Charlie").unwrap();
+        assert_eq!(charlie.vpip.ratio(), Some(0.0));
+        // folding to a reraise was no chance to reraise a single raise
+        assert_eq!(charlie.three_bet.ratio(), None);
+        assert_eq!(charlie.aggression_factor(), None);
+        assert_eq!(
+            charlie.to_string(),
+            "VPIP 0% PFR 0% 3-bet - AF - WTSD -, 1 hands"
+        );
+    }
+
+    #[test]
+    fn test_postflop_stats() {
+        let hud = Hud::new();
+        let hand = CardStack::new(2);
+        play(
+            &hud,
+            &[
+                started(),
+                Event::SmallBlind {
+                    seat: 1,
+                    amount: 1000,
+                },
+                Event::BigBlind {
+                    seat: 2,
+                    amount: 2000,
+                },
+                Event::StreetStarted { street: "Preflop" },
+                Event::Raised { seat: 0, to: 6000 },
+                Event::Called {
+                    seat: 1,
+                    amount: 5000,
+                },
+                Event::Called {
+                    seat: 2,
+                    amount: 4000,
+                },
+                Event::StreetStarted { street: "Flop" },
+                Event::Checked { seat: 1 },
+                Event::Checked { seat: 2 },
+                Event::Bet {
+                    seat: 0,
+                    amount: 9000,
+                },
+                Event::Folded { seat: 1 },
+                Event::Called {
+                    seat: 2,
+                    amount: 9000,
+                },
+                Event::StreetStarted { street: "Turn" },
+                Event::StreetStarted { street: "River" },
+                Event::Shown {
+                    seat: 0,
+                    cards: hand.clone(),
+                },
+                Event::Shown {
+                    seat: 2,
+                    cards: hand.clone(),
+                },
+                Event::PotWon {
+                    seat: 2,
+                    amount: 36000,
+                    hand: Some(hand),
+                },
+            ],
+        );
+
+        let alice = hud.stats("Alice").unwrap();
+        assert_eq!(alice.continuation_bet.ratio(), Some(1.0));
+        assert_eq!(alice.went_to_showdown.ratio(), Some(1.0));
+        assert_eq!(alice.won_at_showdown.ratio(), Some(0.0));
+        assert_eq!(alice.aggression_factor(), None);
+
+        let bob = hud.stats("Bob").unwrap();
+        assert_eq!(bob.fold_to_continuation_bet.ratio(), Some(1.0));
+        assert_eq!(bob.went_to_showdown.ratio(), Some(0.0));
+
+        let charlie = hud.stats("Charlie").unwrap();
+        assert_eq!(charlie.fold_to_continuation_bet.ratio(), Some(0.0));
+        assert_eq!(charlie.won_at_showdown.ratio(), Some(1.0));
+        assert_eq!(charlie.aggression_factor(), Some(0.0));
+        assert_eq!(charlie.vpip.ratio(), Some(1.0));
+    }
+
+    #[test]
+    fn test_stud_stats() {
+        let hud = Hud::new();
+        let mut events = vec![Event::HandStarted {
+            game: "Seven Card Stud".to_string(),
+            button: 0,
+            players: ["Alice", "Bob", "Charlie"].map(String::from).to_vec(),
+            dealt_in: vec![true; 3],
+        }];
+        events.extend((0..3).map(|seat| Event::Ante { seat, amount: 200 }));
+        events.extend([
+            Event::StreetStarted {
+                street: "Third Street",
+            },
+            Event::BringIn {
+                seat: 2,
+                amount: 500,
+            },
+            Event::Raised { seat: 0, to: 2000 },
+            Event::Called {
+                seat: 1,
+                amount: 2000,
+            },
+            Event::Folded { seat: 2 },
+            Event::StreetStarted {
+                street: "Fourth Street",
+            },
+            Event::Bet {
+                seat: 0,
+                amount: 2000,
+            },
+            Event::Folded { seat: 1 },
+            Event::PotWon {
+                seat: 0,
+                amount: 7100,
+                hand: None,
+            },
+        ]);
+        play(&hud, &events);
+
+        // third street counts as preflop and fourth street as the flop
+        let alice = hud.stats("Alice").unwrap();
+        assert_eq!(alice.pfr.ratio(), Some(1.0));
+        assert_eq!(alice.continuation_bet.ratio(), Some(1.0));
+        assert_eq!(alice.went_to_showdown.ratio(), Some(0.0));
+
+        let bob = hud.stats("Bob").unwrap();
+        assert_eq!(bob.vpip.ratio(), Some(1.0));
+        assert_eq!(bob.three_bet.ratio(), Some(0.0));
+        assert_eq!(bob.fold_to_continuation_bet.ratio(), Some(1.0));
+
+        // the bring-in is forced like a blind
+        let charlie = hud.stats("Charlie").unwrap();
+        assert_eq!(charlie.vpip.ratio(), Some(0.0));
+        assert_eq!(
+            charlie.by_position[&TablePosition::Blinds]
+                .vpip
+                .opportunities,
+            1
+        );
+        assert_eq!(charlie.went_to_showdown.opportunities, 0);
+    }
+}
@@ -0,0 +1,431 @@
+use rand::Rng;
+
+use crate::{
+    cards::rank::{self, value_index},
+    get_suit, get_value, Card, Hand, ACE, EIGHT, SUITS,
+};
+
+use super::{actor::PlayerAction, context::GameContext, variant::Evaluator};
+
+/// Preflop strength of two hold'em hole cards, from worst to best
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HandClass {
+    Trash,
+    /// Small pairs, suited aces, suited connectors and offsuit broadway cards
+    Speculative,
+    Playable,
+    Strong,
+    Premium,
+}
+
+impl HandClass {
+    /// The next weaker class, trash stays trash
+    pub fn looser(&self) -> Self {
+        match self {
+            HandClass::Premium => HandClass::Strong,
+            HandClass::Strong => HandClass::Playable,
+            HandClass::Playable => HandClass::Speculative,
+            HandClass::Speculative | HandClass::Trash => HandClass::Trash,
+        }
+    }
+
+    pub fn of(first: Card, second: Card) -> Self {
+        let (high, low) = match value_index(first) >= value_index(second) {
+            true => (value_index(first), value_index(second)),
+            false => (value_index(second), value_index(first)),
+        };
+        let suited = get_suit(first) == get_suit(second);
+        let pair = high == low;
+
+        // indexes: ace 13, king 12, queen 11, jack 10, ten 9
+        match (high, low) {
+            _ if pair && high >= 10 => HandClass::Premium,
+            (13, 12) => HandClass::Premium,
+            _ if pair && high >= 8 => HandClass::Strong,
+            (13, 11) => HandClass::Strong,
+            (13, 10) | (12, 11) if suited => HandClass::Strong,
+            _ if pair && high >= 5 => HandClass::Playable,
+            (13, 9..) | (12, 10..) => HandClass::Playable,
+            (11, 10) | (10, 9) if suited => HandClass::Playable,
+            _ if pair => HandClass::Speculative,
+            (13, _) if suited => HandClass::Speculative,
+            _ if suited && low >= 4 && high - low <= 2 => HandClass::Speculative,
+            _ if low >= 9 => HandClass::Speculative,
+            _ => HandClass::Trash,
+        }
+    }
+}
+
+/// Where a player sits relative to the button, which decides how many hands they play
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TablePosition {
+    Early,
+    Middle,
+    /// The button and the seat before it
+    Late,
+    Blinds,
+}
+
+impl TablePosition {
+    pub fn of(game: &GameContext) -> Self {
+        let dealt_in: Vec<bool> = game
+            .players
+            .iter()
+            .map(|p| !p.sitting_out && !p.eliminated)
+            .collect();
+        let positions = game.positions;
+
+        Self::at(
+            game.seat,
+            positions.button,
+            &[positions.small_blind, positions.big_blind],
+            &dealt_in,
+        )
+    }
+
+    /// Position of `seat` with the button and `blinds` on their seats and `dealt_in` players
+    pub fn at(seat: usize, button: usize, blinds: &[usize], dealt_in: &[bool]) -> Self {
+        if blinds.contains(&seat) {
+            return TablePosition::Blinds;
+        }
+
+        // players dealt in that act after the seat, up to the button
+        let seats = dealt_in.len();
+        let behind = match seat == button {
+            true => 0,
+            false => {
+                (1..seats)
+                    .map(|offset| (seat + offset) % seats)
+                    .take_while(|&other| other != button)
+                    .filter(|&other| dealt_in[other])
+                    .count()
+                    + 1
+            }
+        };
+
+        match behind {
+            0..=1 => TablePosition::Late,
+            2..=3 => TablePosition::Middle,
+            _ => TablePosition::Early,
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            TablePosition::Early => 0,
+            TablePosition::Middle => 1,
+            TablePosition::Late => 2,
+            TablePosition::Blinds => 3,
+        }
+    }
+}
+
+/// How a rule based bot plays, strengths are rough chances to win from 0 to 1
+#[derive(Debug, Clone, PartialEq)]
+pub struct Strategy {
+    /// Weakest hold'em hand played from early, middle and late position and the blinds
+    pub opening: [HandClass; 4],
+    /// Weakest hold'em hand that raises before the flop or calls a raise
+    pub raising: HandClass,
+    /// Strength needed to bet when nobody did yet
+    pub value_threshold: f64,
+    /// Strength needed to raise a bet
+    pub raise_threshold: f64,
+    /// Bets and raises as a fraction of the pot
+    pub bet_size: f64,
+    /// How often a weak hand bets when nobody did yet
+    pub bluff_frequency: f64,
+    /// Equity the bot gives itself on top when it decides whether to call
+    pub stickiness: f64,
+    /// Chance that losing a showdown makes the bot play `tilted` for a while
+    pub tilt: f64,
+}
+
+impl Default for Strategy {
+    fn default() -> Self {
+        Self {
+            opening: [
+                HandClass::Playable,
+                HandClass::Playable,
+                HandClass::Speculative,
+                HandClass::Speculative,
+            ],
+            raising: HandClass::Strong,
+            value_threshold: 0.6,
+            raise_threshold: 0.8,
+            bet_size: 0.66,
+            bluff_frequency: 0.1,
+            stickiness: 0.05,
+            tilt: 0.0,
+        }
+    }
+}
+
+impl Strategy {
+    /// Plays a class of hands more, bluffs twice as often and calls down lighter
+    pub fn tilted(&self) -> Self {
+        Self {
+            opening: self.opening.map(|class| class.looser()),
+            raising: self.raising.looser(),
+            bluff_frequency: (self.bluff_frequency * 2.0).min(1.0),
+            stickiness: self.stickiness + 0.1,
+            ..self.clone()
+        }
+    }
+
+    /// Picks an action out of the ones `game` allows, `rng` decides the bluffs
+    pub fn action(&self, game: &GameContext, rng: &mut impl Rng) -> PlayerAction {
+        let hole = &game.hand.cards;
+        let board = game.boards.first().map_or(&[][..], |b| &b.cards[..]);
+
+        if game.evaluator == Evaluator::High && hole.len() == 2 && board.is_empty() {
+            return self.preflop(game, HandClass::of(hole[0], hole[1]));
+        }
+
+        let strength = strength(game.evaluator, hole, board);
+        let equity = strength.max(draw_equity(game.evaluator, hole, board));
+
+        if game.call_amount == 0 {
+            return match strength >= self.value_threshold || rng.gen_bool(self.bluff_frequency) {
+                true => self.bet(game),
+                false => PlayerAction::Check,
+            };
+        }
+
+        let pot_odds = game.call_amount as f64 / (game.pot + game.call_amount) as f64;
+        if strength >= self.raise_threshold {
+            self.bet(game)
+        } else if equity + self.stickiness >= pot_odds {
+            PlayerAction::Call
+        } else {
+            PlayerAction::Fold
+        }
+    }
+
+    /// Plays hold'em hands by the opening chart of the position
+    fn preflop(&self, game: &GameContext, class: HandClass) -> PlayerAction {
+        let give_up = match game.call_amount {
+            0 => PlayerAction::Check,
+            _ => PlayerAction::Fold,
+        };
+
+        if class < self.opening[TablePosition::of(game).index()] {
+            return give_up;
+        }
+
+        // the last raise is as big as the big blind until somebody raises
+        let raised = game.current_bet > game.min_raise - game.current_bet;
+        match (raised, class >= self.raising) {
+            (false, true) => raise_to(game, game.current_bet * 3),
+            (false, false) if game.call_amount == 0 => PlayerAction::Check,
+            (false, false) => PlayerAction::Call,
+            (true, true) if class == HandClass::Premium => raise_to(game, game.current_bet * 3),
+            (true, true) => PlayerAction::Call,
+            (true, false) => give_up,
+        }
+    }
+
+    /// Bets or raises the configured fraction of the pot after calling
+    fn bet(&self, game: &GameContext) -> PlayerAction {
+        let pot = game.pot + game.call_amount;
+        raise_to(
+            game,
+            game.current_bet + (pot as f64 * self.bet_size) as usize,
+        )
+    }
+}
+
+/// Raises to `to` within the limits, calls or checks when the player can't raise
+pub fn raise_to(game: &GameContext, to: usize) -> PlayerAction {
+    let Some(maximum) = game.max_raise else {
+        return match game.call_amount {
+            0 => PlayerAction::Check,
+            _ => PlayerAction::Call,
+        };
+    };
+
+    let to = to.max(game.min_raise).min(maximum);
+    match to >= game.contribution + game.chips {
+        true => PlayerAction::AllIn,
+        false => PlayerAction::bet_or_raise_to(to, game.current_bet),
+    }
+}
+
+/// Rough chance to win at showdown with the cards made so far
+pub fn strength(evaluator: Evaluator, hole: &[Card], board: &[Card]) -> f64 {
+    match evaluator {
+        Evaluator::Low => low_strength(hole, board, 5, |card| match get_value(card) {
+            ACE => true,
+            value => value <= EIGHT,
+        }),
+        Evaluator::DeuceToSeven => low_strength(hole, board, 5, |card| get_value(card) <= EIGHT),
+        Evaluator::Badugi => {
+            let (mut suits, mut values, mut count) = (0, 0, 0);
+            for &card in hole {
+                if suits & get_suit(card) == 0 && values & get_value(card) == 0 {
+                    suits |= get_suit(card);
+                    values |= get_value(card);
+                    count += 1;
+                }
+            }
+            (count as f64 / hole.len().clamp(1, 4) as f64).powi(2) * 0.8
+        }
+        Evaluator::High | Evaluator::Omaha | Evaluator::HiLo | Evaluator::OmahaHiLo => {
+            let value = match board.len() {
+                0..=2 => evaluator.evaluate(hole, &[]),
+                _ => evaluator.evaluate(hole, board),
+            };
+            let hand = value.rank.hand();
+
+            // a hand the board makes on its own belongs to everybody
+            let hand = match board.len() >= 3 && rank::high(board).hand() >= hand {
+                true => Hand::HighCard,
+                false => hand,
+            };
+
+            let high = match hand {
+                Hand::HighCard => 0.1,
+                Hand::Pair => 0.4,
+                Hand::TwoPair => 0.65,
+                Hand::ThreeOfAKind => 0.75,
+                Hand::Straight => 0.85,
+                Hand::Flush => 0.9,
+                _ => 0.97,
+            };
+
+            match value.low {
+                Some(_) => (high + 0.15f64).min(1.0),
+                None => high,
+            }
+        }
+    }
+}
+
+/// Share of the cards held that are good for a low of `size` cards
+fn low_strength(hole: &[Card], board: &[Card], size: usize, good: impl Fn(Card) -> bool) -> f64 {
+    let cards: Vec<Card> = hole.iter().chain(board).copied().collect();
+    let values = cards
+        .iter()
+        .filter(|&&card| good(card))
+        .fold(0, |acc, &card| acc | get_value(card));
+
+    let made = (values.count_ones() as usize).min(size);
+    (made as f64 / cards.len().clamp(1, size) as f64).powi(2) * 0.8
+}
+
+/// Chance to hit a flush or straight with the community cards still to come
+pub fn draw_equity(evaluator: Evaluator, hole: &[Card], board: &[Card]) -> f64 {
+    if !matches!(evaluator, Evaluator::High | Evaluator::HiLo) || !(3..5).contains(&board.len()) {
+        return 0.0;
+    }
+
+    let cards: Vec<Card> = hole.iter().chain(board).copied().collect();
+    let to_come = 5 - board.len();
+
+    let flush_draw = SUITS
+        .iter()
+        .any(|&suit| cards.iter().filter(|&&c| get_suit(c) == suit).count() == 4);
+
+    // the ace plays high and low
+    let values = cards.iter().fold(0, |acc, &c| acc | get_value(c));
+    let values = match values & ACE {
+        0 => values,
+        _ => values | 1,
+    };
+    let straight_draw = (0..=10).any(|low| (values >> low) & 0b1111 == 0b1111)
+        && (0..=9).all(|low| (values >> low) & 0b11111 != 0b11111);
+
+    let outs = match (flush_draw, straight_draw) {
+        (true, true) => 15,
+        (true, false) => 9,
+        (false, true) => 8,
+        (false, false) => 0,
+    };
+
+    // two percent per out and card to come
+    (outs * 2 * to_come) as f64 / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::game::{
+        hand::{apply, new_game, Action, HandState},
+        variants::Holdem,
+    };
+    use crate::*;
+
+    /// Three handed hold'em after the deal, the button is first to act
+    fn dealt_hand(button_cards: [Card; 2]) -> HandState {
+        let state = new_game(Arc::new(Holdem::default()), &[20000; 3]);
+        let (state, _) = apply(&state, Action::PostBlinds).unwrap();
+        let (mut state, _) = apply(&state, Action::Deal).unwrap();
+        state.players[0].hand = CardStack::from(button_cards.to_vec());
+        state
+    }
+
+    #[test]
+    fn test_preflop_chart() {
+        let strategy = Strategy::default();
+        let mut rng = StdRng::seed_from_u64(1);
+
+        let state = dealt_hand([ACE | SPADE, ACE | HEART]);
+        let context = GameContext::new(&state, 0);
+        assert_eq!(TablePosition::of(&context), TablePosition::Late);
+        assert_eq!(
+            strategy.action(&context, &mut rng),
+            PlayerAction::RaiseTo(6000)
+        );
+
+        let state = dealt_hand([SEVEN | SPADE, TWO | HEART]);
+        assert_eq!(
+            strategy.action(&GameContext::new(&state, 0), &mut rng),
+            PlayerAction::Fold
+        );
+
+        // the big blind checks its option when nobody raised
+        let (state, _) = apply(&state, Action::Act(0, PlayerAction::Call)).unwrap();
+        let (mut state, _) = apply(&state, Action::Act(1, PlayerAction::Call)).unwrap();
+        state.players[2].hand = CardStack::from(vec![EIGHT | CLUB, THREE | DIAMOND]);
+        let context = GameContext::new(&state, 2);
+        assert_eq!(TablePosition::of(&context), TablePosition::Blinds);
+        assert_eq!(strategy.action(&context, &mut rng), PlayerAction::Check);
+    }
+
+    #[test]
+    fn test_hand_classes() {
+        assert_eq!(HandClass::of(ACE | SPADE, ACE | HEART), HandClass::Premium);
+        assert_eq!(HandClass::of(KING | CLUB, ACE | HEART), HandClass::Premium);
+        assert_eq!(HandClass::of(NINE | CLUB, NINE | HEART), HandClass::Strong);
+        assert_eq!(HandClass::of(ACE | CLUB, TEN | HEART), HandClass::Playable);
+        assert_eq!(
+            HandClass::of(SIX | CLUB, SEVEN | CLUB),
+            HandClass::Speculative
+        );
+        assert_eq!(HandClass::of(TWO | CLUB, SEVEN | HEART), HandClass::Trash);
+    }
+
+    #[test]
+    fn test_strength() {
+        let hole = [ACE | SPADE, KING | SPADE];
+        let board = [ACE | HEART, SEVEN | CLUB, TWO | DIAMOND];
+        assert_eq!(strength(Evaluator::High, &hole, &board), 0.4);
+
+        // the pair on the board is shared by everybody
+        let board = [SEVEN | HEART, SEVEN | CLUB, TWO | DIAMOND];
+        assert_eq!(strength(Evaluator::High, &hole, &board), 0.1);
+
+        let board = [QUEEN | SPADE, SEVEN | SPADE, TWO | DIAMOND];
+        assert_eq!(draw_equity(Evaluator::High, &hole, &board), 0.36);
+
+        let board = [QUEEN | HEART, JACK | CLUB, TWO | DIAMOND, FOUR | CLUB];
+        assert_eq!(draw_equity(Evaluator::High, &hole, &board), 0.16);
+        let board = [QUEEN | HEART, JACK | CLUB, TEN | DIAMOND, FOUR | CLUB];
+        assert_eq!(draw_equity(Evaluator::High, &hole, &board), 0.0);
+        assert_eq!(strength(Evaluator::High, &hole, &board), 0.85);
+    }
+}
@@ -1,223 +1,611 @@
-use crate::{game::actor::Actionable, CardStack};
-
-use super::{actor::PlayerAction, context::GameContext, player::Player};
-
-pub enum GameState {
-    PreFlop,
-    Flop,
-    Turn,
-    River,
-    Showdown,
-}
-
-pub struct TexasHoldem {
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::{
+    game::actor::{Actionable, AsyncActionable, SyncActor},
+    util::executor::{block_on, with_deadline},
+    CardStack,
+};
+
+use super::{
+    context::GameContext,
+    hand::{apply, legal_actions, validate, validate_draw, Action, Event, HandState},
+    observer::Observer,
+    player::{OwedBlinds, Player},
+    rotation::Rotation,
+    seats::{Positions, SeatError},
+    settings::{ButtonRule, TimeLimit},
+    signals::TableSignals,
+    stats::Hud,
+    variant::Variant,
+    variants::Holdem,
+};
+
+/// A table that can run any poker game, the rules come from its variant
+pub struct Table {
+    pub variant: Arc<dyn Variant>,
     pub players: Vec<Player>,
-    pub actors: Vec<Box<dyn Actionable>>,
+    pub actors: Vec<Arc<dyn AsyncActionable>>,
+    pub seats: usize,
     pub blinds: usize,
     pub buy_in: usize,
-    pub state: GameState,
+    /// Biggest stack players can top up to
+    pub max_buy_in: usize,
+    /// Chips an add-on buys, on top of the table maximum
+    pub add_on: Option<usize>,
+    /// Players that left the table, with the chips they cashed out
+    pub departed: Vec<Player>,
     pub dealer: usize,
+    pub button_rule: ButtonRule,
+    /// Players get unlimited time to decide without one
+    pub time_limit: Option<TimeLimit>,
+    /// Time bank left for every seat
+    pub time_banks: Vec<Duration>,
+    pub hands_played: usize,
+    /// Games a mixed table switches between, the variant and blinds follow it
+    pub rotation: Option<Rotation>,
+    /// What everybody at the table sees, as signals for UIs
+    pub signals: TableSignals,
+    /// Loggers, trackers and everybody else that follows the table without a seat
+    pub observers: Vec<Arc<dyn Observer>>,
+    /// Stats of everybody that played at the table, players get them with every decision
+    pub hud: Arc<Hud>,
+    /// Plays without printing anything, for headless matches
+    pub quiet: bool,
+    /// Shuffles the decks of the table, they are random without a seed
+    deck_rng: Option<StdRng>,
+    positions: Option<Positions>,
 }
 
-impl TexasHoldem {
+/// The table started out as a hold'em table, which is still the default game
+pub type TexasHoldem = Table;
+
+impl Table {
+    /// A no limit hold'em table
     pub fn new(players: usize, blinds: usize, buy_in: usize) -> Self {
-        return Self {
+        Self::with_variant(Arc::new(Holdem::default()), players, blinds, buy_in)
+    }
+
+    pub fn with_variant(
+        variant: Arc<dyn Variant>,
+        players: usize,
+        blinds: usize,
+        buy_in: usize,
+    ) -> Self {
+        let players = players.min(variant.max_players());
+        let signals = TableSignals::default();
+        signals.game.set(variant.name());
+
+        Self {
+            variant,
             players: Vec::with_capacity(players),
+            seats: players,
             blinds,
             buy_in,
+            max_buy_in: buy_in,
+            add_on: None,
+            departed: vec![],
             actors: Vec::with_capacity(players),
-            state: GameState::PreFlop,
             dealer: 0,
-        };
+            button_rule: ButtonRule::default(),
+            time_limit: None,
+            time_banks: Vec::with_capacity(players),
+            hands_played: 0,
+            rotation: None,
+            signals,
+            observers: vec![],
+            hud: Arc::new(Hud::new()),
+            quiet: false,
+            deck_rng: None,
+            positions: None,
+        }
+    }
+
+    /// A mixed table with as many seats as every game of the rotation allows
+    pub fn with_rotation(rotation: Rotation, players: usize, buy_in: usize) -> Self {
+        let game = rotation.game().clone();
+        let players = players.min(rotation.max_players());
+
+        Self {
+            rotation: Some(rotation),
+            ..Self::with_variant(game.variant, players, game.blinds, buy_in)
+        }
     }
 
     pub fn add_player(&mut self, name: String, p: Box<dyn Actionable>) {
-        self.players.push(Player::new(name, self.buy_in));
-        self.actors.push(p);
+        self.join(name, p).expect("The table is full");
+    }
+
+    /// Seats a new player with a buy in on the first free seat and returns the seat.
+    /// Once the game is running the player posts a big blind to get dealt in.
+    pub fn join(&mut self, name: String, p: Box<dyn Actionable>) -> Result<usize, SeatError> {
+        self.join_async(name, Arc::new(SyncActor::from(p)))
+    }
+
+    /// Seats a player whose decisions the table awaits, see `join`
+    pub fn join_async(
+        &mut self,
+        name: String,
+        actor: Arc<dyn AsyncActionable>,
+    ) -> Result<usize, SeatError> {
+        let mut player = Player::new(name, self.buy_in);
+        if self.hands_played > 0 {
+            player.owed_blinds = OwedBlinds::BigBlind;
+        }
+
+        let time_bank = self.time_limit.map_or(Duration::ZERO, |l| l.time_bank);
+
+        if let Some(seat) = self.players.iter().position(|p| p.left) {
+            self.players[seat] = player;
+            self.actors[seat] = actor;
+            self.time_banks[seat] = time_bank;
+            return Ok(seat);
+        }
+
+        if self.players.len() >= self.seats {
+            return Err(SeatError::TableFull);
+        }
+
+        self.players.push(player);
+        self.actors.push(actor);
+        self.time_banks.push(time_bank);
+        Ok(self.players.len() - 1)
+    }
+
+    /// Tells `observer` about every public event from the next hand on
+    pub fn observe(&mut self, observer: Arc<dyn Observer>) {
+        self.observers.push(observer);
+    }
+
+    fn seated(&mut self, seat: usize) -> Result<&mut Player, SeatError> {
+        self.players
+            .get_mut(seat)
+            .filter(|p| !p.left)
+            .ok_or(SeatError::EmptySeat { seat })
+    }
+
+    /// Cashes out the player on `seat` and frees the seat
+    pub fn leave(&mut self, seat: usize) -> Result<Player, SeatError> {
+        let player = self.seated(seat)?;
+        let departed = player.clone();
+
+        player.left = true;
+        player.chips = 0;
+        player.bought_in = 0;
+
+        self.departed.push(departed.clone());
+        Ok(departed)
+    }
+
+    /// Keeps the player on `seat` out of the next hands, the blinds they miss are owed
+    pub fn sit_out(&mut self, seat: usize) -> Result<(), SeatError> {
+        self.seated(seat)?.sitting_out = true;
+        Ok(())
+    }
+
+    pub fn sit_in(&mut self, seat: usize) -> Result<(), SeatError> {
+        let player = self.seated(seat)?;
+        if player.eliminated {
+            return Err(SeatError::Busted { seat });
+        }
+
+        player.sitting_out = false;
+        Ok(())
+    }
+
+    /// Buys a busted player back in for the table's buy in
+    pub fn rebuy(&mut self, seat: usize) -> Result<(), SeatError> {
+        let buy_in = self.buy_in;
+        let player = self.seated(seat)?;
+        if !player.eliminated {
+            return Err(SeatError::NotBusted { seat });
+        }
+
+        player.eliminated = false;
+        player.chips = buy_in;
+        player.bought_in += buy_in;
+        Ok(())
+    }
+
+    /// Buys `amount` more chips, as long as the stack stays within the table maximum
+    pub fn top_up(&mut self, seat: usize, amount: usize) -> Result<(), SeatError> {
+        let maximum = self.max_buy_in;
+        let player = self.seated(seat)?;
+        if player.eliminated {
+            return Err(SeatError::Busted { seat });
+        }
+
+        if player.chips + amount > maximum {
+            return Err(SeatError::AboveMaximum { maximum });
+        }
+
+        player.chips += amount;
+        player.bought_in += amount;
+        Ok(())
+    }
+
+    /// Buys the table's add-on, which is allowed to go over the table maximum
+    pub fn add_on(&mut self, seat: usize) -> Result<(), SeatError> {
+        let amount = self.add_on.ok_or(SeatError::NoAddOn)?;
+        let player = self.seated(seat)?;
+        if player.eliminated {
+            return Err(SeatError::Busted { seat });
+        }
+
+        player.chips += amount;
+        player.bought_in += amount;
+        Ok(())
+    }
+
+    /// Chips won or lost by everybody that played at the table, including players that left
+    pub fn balances(&self) -> Vec<(String, isize)> {
+        self.players
+            .iter()
+            .filter(|p| !p.left)
+            .chain(self.departed.iter())
+            .map(|p| (p.name.clone(), p.balance()))
+            .collect()
+    }
+
+    /// Fills up the time banks when the time limit says so
+    fn refill_time_banks(&mut self) {
+        if let Some(limit) = self.time_limit {
+            if self.hands_played == 0 || limit.refills_before(self.hands_played) {
+                self.time_banks
+                    .iter_mut()
+                    .for_each(|t| *t = limit.time_bank);
+            }
+        }
     }
 
     pub fn play(&mut self) {
-        loop {
-            self.play_round();
-            self.dealer += 1;
+        while self.play_hand() {}
 
-            if self.players.iter().filter(|p| !p.eliminated).count() == 1 {
-                // player won!
-                break;
+        if !self.quiet {
+            println!("Game over");
+        }
+    }
+
+    /// Deals the same cards every time the table is seeded the same way
+    pub fn seed(&mut self, seed: u64) {
+        self.deck_rng = Some(StdRng::seed_from_u64(seed));
+    }
+
+    /// Plays a single hand, players can join, leave or buy chips in between.
+    /// Returns false if there are not enough players for a hand.
+    pub fn play_hand(&mut self) -> bool {
+        block_on(self.play_hand_async())
+    }
+
+    /// Plays a single hand on the caller's executor, see `play_hand`
+    pub async fn play_hand_async(&mut self) -> bool {
+        if self.players.iter().filter(|p| p.is_playing()).count() < 2 {
+            return false;
+        }
+
+        self.play_round().await;
+        true
+    }
+
+    /// Seats of players that get dealt into the next hand
+    fn live_seats(&self) -> Vec<bool> {
+        self.players.iter().map(|p| p.is_playing()).collect()
+    }
+
+    /// Players sitting out owe the blinds once the big blind passes their seat
+    fn mark_missed_blinds(&mut self, previous: Positions, current: Positions) {
+        let len = self.players.len();
+        let mut seat = (previous.big_blind + 1) % len;
+
+        while seat != current.big_blind && seat != previous.big_blind {
+            let player = &mut self.players[seat];
+            if player.sitting_out && !player.eliminated && !player.left {
+                player.owed_blinds = OwedBlinds::Both;
             }
+
+            seat = (seat + 1) % len;
         }
+    }
+
+    /// Moves the button and blinds for the next hand
+    fn next_positions(&mut self) -> Positions {
+        let live = self.live_seats();
+        let positions = match self.positions {
+            Some(positions) => positions.next(&live, self.button_rule),
+            None => Positions::first(&live, self.dealer),
+        }
+        .expect("At least two players are needed to play a hand");
 
-        println!("Game over");
+        self.positions = Some(positions);
+        self.dealer = positions.button;
+        positions
     }
 
-    fn play_round(&mut self) {
-        let mut community_cards: CardStack = CardStack::new(5);
+    /// Plays a hand by feeding the actors' decisions and the dealer's steps into `apply`
+    async fn play_round(&mut self) {
+        self.refill_time_banks();
+        let previous = self.positions;
+        let positions = self.next_positions();
+        if let Some(previous) = previous {
+            self.mark_missed_blinds(previous, positions);
+        }
+        self.rotate(positions).await;
+
         let mut deck = CardStack::standard_deck();
+        if let Some(rng) = self.deck_rng.as_mut() {
+            // the standard deck comes shuffled already
+            deck.cards.sort();
+            deck.cards.shuffle(rng);
+        }
 
-        let mut rounds = 0;
+        let mut state = HandState::new(
+            Arc::clone(&self.variant),
+            self.players.clone(),
+            positions,
+            self.blinds,
+            deck,
+        );
+        self.signals.update(&state);
 
         loop {
-            let active_player_count: usize = self.players.iter().filter(|p| !p.folded).count();
-
-            self.state = match self.state {
-                GameState::PreFlop => {
-                    for _ in 0..2 {
-                        for player in self.players.iter_mut() {
-                            let card = deck
-                                .pop()
-                                .expect("Could not deal cards because the deck is empty");
-
-                            player.hand.push(card)
-                        }
-                    }
-                    GameState::Flop
-                }
-                GameState::Flop => {
-                    for _ in 0..3 {
-                        let card = deck
-                            .pop()
-                            .expect("Could not deal cards because the deck is empty");
-                        community_cards.push(card);
-                    }
-
-                    GameState::Turn
-                }
-                GameState::Turn => {
-                    let card = deck
-                        .pop()
-                        .expect("Could not deal cards because the deck is empty");
-                    community_cards.push(card);
-                    GameState::River
-                }
-                GameState::River => {
-                    let card = deck
-                        .pop()
-                        .expect("Could not deal cards because the deck is empty");
-                    community_cards.push(card);
-                    GameState::Showdown
-                }
-                GameState::Showdown => {
-                    // get all active players cards
-                    // compare against each other
-                    // get winner
-                    // move pot to winner
-                    todo!()
-                }
+            let action = match (state.to_act, state.to_draw) {
+                (Some(seat), _) => self.request_action(&state, seat).await,
+                (None, Some(seat)) => self.request_discard(&state, seat).await,
+                (None, None) => match legal_actions(&state).first() {
+                    Some(&action) => action,
+                    None => break,
+                },
             };
 
-            if rounds == 1 {
-                current_player.bet = self.blinds;
-                println!(
-                    "{}: Paying small blind: {}",
-                    current_player.name,
-                    self.blinds / 2
-                );
-                continue;
+            let (next, events) = apply(&state, action).expect("Only legal actions are applied");
+            state = next;
+            self.signals.update(&state);
+
+            for event in events.iter() {
+                self.print_event(&state, event);
+                self.broadcast(event);
             }
+        }
+
+        self.players = state.players;
+        self.hands_played += 1;
+        self.signals.hands_played.set(self.hands_played);
+        if let Some(rotation) = self.rotation.as_mut() {
+            rotation.hand_played();
+        }
 
-            if rounds == 2 {
-                current_player.bet = self.blinds / 2;
-                println!(
-                    "{}: Paying large blind: {}",
-                    current_player.name, self.blinds
-                );
+        for seat in 0..self.players.len() {
+            let player = &mut self.players[seat];
+            if !player.is_playing() || player.chips > 0 {
                 continue;
             }
 
-            // betting round
-            loop {
-                rounds += 1;
-
-                let current_player_idx = (self.dealer + rounds) % self.players.len();
-                let bets: Vec<usize> = self.players.iter().map(|p| p.bet).collect();
-
-                let pot: usize = bets.iter().sum();
-                let current_bet = *bets.iter().max().expect("No bets?");
-
-                let current_player = self
-                    .players
-                    .get(current_player_idx)
-                    .expect("Some player is missing");
-
-                let context = GameContext {
-                    pot,
-                    current_bet: current_bet,
-                    call_amount: current_bet - current_player.bet,
-                    min_raise: current_bet * 2,
-                    contribution: current_player.bet,
-                    side_pots: vec![],
-                    community: community_cards.clone(),
-                    hand: current_player.hand.clone(),
-                    chips: current_player.chips,
-                    players: self.players.clone().into_iter().map(|p| p.into()).collect(),
-                    player_contributions: bets.clone(),
-                };
-
-                let current_player = self
-                    .players
-                    .get_mut(current_player_idx)
-                    .expect("Some player is missing");
-
-                // break the loop when all active players have contributed the same amount
-                if self
-                    .players
-                    .iter()
-                    .filter(|p| !p.folded && !p.eliminated)
-                    .all(|p| p.bet == current_bet)
-                {
-                    println!("All players have contributed the same amount, time to move on");
-                    break;
-                }
+            player.eliminated = true;
+            if !self.quiet {
+                println!("{}: I'm eliminated", player.name);
+            }
 
-                if current_player.folded || current_player.eliminated || current_player.chips == 0 {
-                    println!("{}: I'm out", current_player.name);
-                    continue;
+            if self.actors[seat]
+                .wants_rebuy(self.buy_in)
+                .await
+                .unwrap_or(false)
+            {
+                self.rebuy(seat).expect("Busted players can rebuy");
+                if !self.quiet {
+                    println!("{}: I rebuy for {}", self.players[seat].name, self.buy_in);
                 }
+            }
+        }
+    }
 
-                let action = self.actors[current_player_idx].action(&context);
-
-                match action {
-                    PlayerAction::Call => {
-                        let call_amount = current_bet - current_player.bet;
+    /// Tells the players and observers about `event`, as far as they get to see it
+    fn broadcast(&self, event: &Event) {
+        for (seat, actor) in self.actors.iter().enumerate() {
+            if !self.players[seat].left && event.is_visible_to(seat) {
+                actor.notify(event);
+            }
+        }
 
-                        if call_amount == 0 {
-                            println!("{}: I Check", current_player.name);
-                        } else {
-                            println!("{}: I Call {}", current_player.name, call_amount);
-                        }
+        if event.is_public() {
+            self.hud.notify(event);
+            self.observers.iter().for_each(|o| o.notify(event));
+        }
+    }
 
-                        if current_player.chips - call_amount <= 0 {
-                            println!("{}: I'm all in", current_player.name);
-                            current_player.bet += current_player.chips;
-                            current_player.chips = 0;
-                            // handle side pots
-                            continue;
-                        }
+    /// Switches a mixed table to its next game once the current one has been played long enough.
+    /// In dealer's choice the player on the button picks it
+    async fn rotate(&mut self, positions: Positions) {
+        let players = self.live_seats().iter().filter(|&&live| live).count();
+        let rotation = match self.rotation.as_mut() {
+            Some(rotation) if rotation.is_due() => rotation,
+            _ => return,
+        };
 
-                        current_player.bet += call_amount;
-                        current_player.chips -= call_amount;
-                    }
-                    PlayerAction::Raise(amount) => {
-                        println!("{}: I Raise {}", current_player.name, amount);
-
-                        if current_player.chips - amount <= 0 {
-                            println!("{}: I'm all in", current_player.name);
-                            current_player.bet += current_player.chips;
-                            current_player.chips = 0;
-                            // handle side pots
-                            continue;
-                        }
-
-                        current_player.bet += amount;
-                        current_player.chips -= amount;
-                    }
-                    PlayerAction::Fold => {
-                        println!("{}: I Fold", current_player.name);
-                        current_player.folded = true;
-                        continue;
+        let next = if rotation.dealers_choice {
+            let choice = self.actors[positions.button]
+                .choose_game(rotation.names())
+                .await;
+            match choice {
+                Some(choice) if choice < rotation.games.len() => choice,
+                Some(choice) => {
+                    if !self.quiet {
+                        println!(
+                            "{}: Illegal game choice, {} is not an option",
+                            self.players[positions.button].name, choice
+                        );
                     }
+                    rotation.next_in_order()
                 }
+                None => rotation.next_in_order(),
+            }
+        } else {
+            rotation.next_in_order()
+        };
+
+        let game = rotation.switch(next, players).clone();
+        if !self.quiet {
+            println!("Now playing {} at {}", game.variant.name(), game.blinds);
+        }
+        self.signals.game.set(game.variant.name());
+
+        self.variant = game.variant;
+        self.blinds = game.blinds;
+    }
+
+    /// Asks the actor on `seat` for an action and handles illegal ones with its policy
+    async fn request_action(&mut self, state: &HandState, seat: usize) -> Action {
+        let actor = Arc::clone(&self.actors[seat]);
+        let context = GameContext::new(state, seat).with_stats(&self.hud);
+        let started = Instant::now();
+        let deadline = self.deadline(seat, started);
+        let mut attempt = 0;
+
+        let action = loop {
+            let decision = with_deadline(actor.action(context.clone()), deadline).await;
+            let action = match decision.flatten() {
+                Some(action) => action,
+                // an actor that crashed gets treated like one that ran out of time
+                None => break Action::TimeOut(seat),
+            };
+
+            let error = match validate(state, seat, &action) {
+                Ok(()) => break Action::Act(seat, action),
+                Err(error) => error,
+            };
+
+            attempt += 1;
+            if !self.quiet {
+                println!("{}: Illegal action, {}", state.players[seat].name, error);
+            }
+
+            if let Some(action) = actor.illegal_action_policy().coerce(attempt) {
+                break Action::Act(seat, action);
+            }
+        };
+
+        self.use_time_bank(seat, started);
+        action
+    }
+
+    /// Asks the actor on `seat` which cards to throw away in a draw
+    async fn request_discard(&mut self, state: &HandState, seat: usize) -> Action {
+        let actor = Arc::clone(&self.actors[seat]);
+        let context = GameContext::new(state, seat).with_stats(&self.hud);
+        let started = Instant::now();
+        let deadline = self.deadline(seat, started);
+        let mut attempt = 0;
+
+        let action = loop {
+            let decision = with_deadline(actor.discard(context.clone()), deadline).await;
+            let discard = match decision.flatten() {
+                Some(discard) => discard,
+                None => break Action::TimeOut(seat),
+            };
+
+            let error = match validate_draw(state, seat, &discard) {
+                Ok(()) => break Action::Draw(seat, discard),
+                Err(error) => error,
+            };
+
+            attempt += 1;
+            if !self.quiet {
+                println!("{}: Illegal discard, {}", state.players[seat].name, error);
+            }
+
+            let fallback = state.default_discard();
+            if let Some(discard) = actor
+                .illegal_action_policy()
+                .coerce_discard(attempt, fallback)
+            {
+                break Action::Draw(seat, discard);
+            }
+        };
+
+        self.use_time_bank(seat, started);
+        action
+    }
+
+    /// When the player on `seat` runs out of time for a decision asked for at `started`
+    fn deadline(&self, seat: usize, started: Instant) -> Option<Instant> {
+        self.time_limit
+            .map(|limit| started + limit.decision + self.time_banks[seat])
+    }
+
+    /// Time over the limit for the decision comes out of the time bank
+    fn use_time_bank(&mut self, seat: usize, started: Instant) {
+        if let Some(limit) = self.time_limit {
+            let overtime = started.elapsed().saturating_sub(limit.decision);
+            self.time_banks[seat] = self.time_banks[seat].saturating_sub(overtime);
+        }
+    }
+
+    fn print_event(&self, state: &HandState, event: &Event) {
+        if self.quiet {
+            return;
+        }
+
+        let name = |seat: usize| state.players[seat].name.as_str();
+
+        match event {
+            Event::Ante { seat, amount } => println!("{}: Paying ante: {}", name(*seat), amount),
+            Event::BringIn { seat, amount } => {
+                println!("{}: Bringing it in for {}", name(*seat), amount)
+            }
+            Event::SmallBlind { seat, amount } => {
+                println!("{}: Paying small blind: {}", name(*seat), amount)
             }
+            Event::DeadSmallBlind => println!("Dead small blind"),
+            Event::OwedBlinds { seat, live, dead } => println!(
+                "{}: Paying missed blinds: {} live, {} dead",
+                name(*seat),
+                live,
+                dead
+            ),
+            Event::BigBlind { seat, amount } => {
+                println!("{}: Paying big blind: {}", name(*seat), amount)
+            }
+            Event::HandStarted { .. } | Event::StreetStarted { .. } | Event::HoleCards { .. } => {}
+            Event::UpCards { seat, cards } => println!("{}: Dealt {}", name(*seat), cards),
+            Event::Community {
+                street,
+                board: 0,
+                cards,
+            } => println!("{}: {}", street, cards),
+            Event::Community {
+                street,
+                board,
+                cards,
+            } => println!("{} on board {}: {}", street, board + 1, cards),
+            Event::Checked { seat } => println!("{}: I Check", name(*seat)),
+            Event::Called { seat, amount } => println!("{}: I Call {}", name(*seat), amount),
+            Event::Bet { seat, amount } => println!("{}: I Bet {}", name(*seat), amount),
+            Event::Raised { seat, to } => println!("{}: I Raise to {}", name(*seat), to),
+            Event::Folded { seat } => println!("{}: I Fold", name(*seat)),
+            Event::AllIn { seat } => println!("{}: I'm all in", name(*seat)),
+            Event::TimedOut { seat } => println!("{}: Out of time", name(*seat)),
+            Event::Drew { seat, count: 0 } => println!("{}: I stand pat", name(*seat)),
+            Event::Drew { seat, count } => println!("{}: I draw {}", name(*seat), count),
+            Event::Discarded { seat, count } => {
+                println!("{}: I discard {}", name(*seat), count)
+            }
+            Event::BettingClosed => {
+                println!("All players have contributed the same amount, time to move on")
+            }
+            Event::Shown { seat, cards } => println!("{}: I show {}", name(*seat), cards),
+            Event::PotWon {
+                seat,
+                amount,
+                hand: Some(hand),
+            } => println!("{}: I win {} with {}", name(*seat), amount, hand),
+            Event::PotWon {
+                seat,
+                amount,
+                hand: None,
+            } => println!("{}: I win {}", name(*seat), amount),
         }
     }
 }
@@ -225,7 +613,25 @@ impl TexasHoldem {
 #[cfg(test)]
 mod tests {
 
+    use std::{thread, time::Duration};
+
+    use crate::game::{
+        actor::{Actionable, AsyncActionable, Decision, PlayerAction, SyncActor},
+        context::GameContext,
+        hand::Event,
+        observer::Observer,
+        player::{OwedBlinds, Player},
+        rotation::{Period, Rotation},
+        seats::SeatError,
+        settings::{ButtonRule, GameType, TimeBankRefill, TimeLimit},
+        simulation::MonteCarlo,
+        variant::Variant,
+        variants::{
+            Badugi, Courchevel, DeuceToSeven, FiveCardDraw, Irish, Omaha, Pineapple, SevenCardStud,
+        },
+    };
     use crate::*;
+    use std::sync::{Arc, Mutex};
 
     #[test]
     fn test_adding_users() {
@@ -244,14 +650,359 @@ mod tests {
     #[test]
     fn test_basic_rules() {
         let mut game = TexasHoldem::new(10, 2000, 20000);
+        game.quiet = true;
+        game.seed(1);
 
-        game.add_player("Alice".to_string(), Box::new(NPC::new("Alice".to_string())));
+        for (seed, name) in ["Alice", "Bob", "Charlie"].iter().enumerate() {
+            let bot = NPC::new(name.to_string()).with_seed(seed as u64);
+            game.add_player(name.to_string(), Box::new(bot));
+        }
+
+        // bots that fold to each other can pass the blinds around forever
+        for _ in 0..50 {
+            if !game.play_hand() {
+                break;
+            }
+        }
+
+        assert_eq!(game.players.iter().map(|p| p.chips).sum::<usize>(), 60000);
+    }
+
+    #[test]
+    fn test_winner_takes_all_chips() {
+        let mut game = TexasHoldem::new(10, 2000, 20000);
+        game.button_rule = ButtonRule::MovingButton;
+        game.quiet = true;
+        game.seed(2);
+
+        for (seed, name) in ["Alice", "Bob", "Charlie"].iter().enumerate() {
+            let bot = NPC::new(name.to_string()).with_seed(seed as u64);
+            game.add_player(name.to_string(), Box::new(bot));
+        }
+
+        // the seeds play the same hands every time, this many end the game
+        for _ in 0..500 {
+            if !game.play_hand() {
+                break;
+            }
+        }
+
+        let winners: Vec<&Player> = game.players.iter().filter(|p| !p.eliminated).collect();
+        assert_eq!(winners.len(), 1);
+        assert_eq!(winners[0].chips, 60000);
+    }
+
+    struct SlowPlayer;
+    impl Actionable for SlowPlayer {
+        fn action(&self, _game: &GameContext) -> PlayerAction {
+            thread::sleep(Duration::from_millis(100));
+            PlayerAction::Call
+        }
+    }
+
+    #[test]
+    fn test_slow_players_time_out() {
+        let mut game = TexasHoldem::new(10, 2000, 20000);
+        game.time_limit = Some(TimeLimit {
+            decision: Duration::from_millis(5),
+            time_bank: Duration::from_millis(20),
+            refill: TimeBankRefill::Never,
+        });
+
+        game.add_player("Slow".to_string(), Box::new(SlowPlayer));
         game.add_player("Bob".to_string(), Box::new(NPC::new("Bob".to_string())));
-        game.add_player(
-            "Charlie".to_string(),
-            Box::new(NPC::new("Charlie".to_string())),
+
+        game.play_hand();
+
+        // the slow player is on the button, times out preflop and folds the small blind
+        assert_eq!(game.time_banks[0], Duration::ZERO);
+        assert_eq!(game.players[0].chips, 19000);
+        assert_eq!(game.players[1].chips, 21000);
+    }
+
+    struct Crashing;
+    impl Actionable for Crashing {
+        fn action(&self, _game: &GameContext) -> PlayerAction {
+            panic!("Buggy bot")
+        }
+    }
+
+    #[test]
+    fn test_crashed_players_time_out() {
+        let mut game = TexasHoldem::new(10, 2000, 20000);
+        game.quiet = true;
+        game.add_player("Crashing".to_string(), Box::new(Crashing));
+        game.add_player("Bob".to_string(), Box::new(NPC::new("Bob".to_string())));
+
+        assert!(game.play_hand());
+
+        // the crash folds the small blind like a timeout would, the table plays on
+        assert_eq!(game.players[0].chips, 19000);
+        assert_eq!(game.players[1].chips, 21000);
+        assert!(game.play_hand());
+    }
+
+    /// Never hears back from its client
+    struct Disconnected;
+    impl AsyncActionable for Disconnected {
+        fn action(&self, _game: GameContext) -> Decision<'_, PlayerAction> {
+            Box::pin(std::future::pending())
+        }
+    }
+
+    #[test]
+    fn test_async_actors() {
+        let mut game = TexasHoldem::new(10, 2000, 20000);
+        game.time_limit = Some(TimeLimit {
+            decision: Duration::from_millis(5),
+            time_bank: Duration::ZERO,
+            refill: TimeBankRefill::Never,
+        });
+
+        game.join_async("Remote".to_string(), Arc::new(Disconnected))
+            .unwrap();
+        game.add_player("Bob".to_string(), Box::new(NPC::new("Bob".to_string())));
+
+        assert!(game.play_hand());
+
+        // the remote player times out and folds the small blind, the signals follow the table
+        assert_eq!(game.players[0].chips, 19000);
+        assert_eq!(game.signals.chips.get_cloned(), vec![19000, 21000]);
+        assert_eq!(game.signals.hands_played.get(), 1);
+        assert_eq!(game.signals.game.get_cloned(), "No Limit Texas Hold'em");
+        assert_eq!(game.signals.to_act.get(), None);
+    }
+
+    /// Calls everything and remembers what it was told
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<Event>>);
+    impl Actionable for Recorder {
+        fn action(&self, _game: &GameContext) -> PlayerAction {
+            PlayerAction::Call
+        }
+
+        fn notify(&self, event: &Event) {
+            self.0.lock().unwrap().push(event.clone());
+        }
+    }
+    impl Observer for Recorder {
+        fn notify(&self, event: &Event) {
+            self.0.lock().unwrap().push(event.clone());
+        }
+    }
+
+    #[test]
+    fn test_observers() {
+        let player = Arc::new(Recorder::default());
+        let observer = Arc::new(Recorder::default());
+
+        let mut game = TexasHoldem::new(10, 2000, 20000);
+        game.join_async(
+            "Alice".to_string(),
+            Arc::new(SyncActor::new(Arc::clone(&player) as Arc<dyn Actionable>)),
+        )
+        .unwrap();
+        game.add_player("Bob".to_string(), Box::new(Recorder::default()));
+        game.observe(Arc::clone(&observer) as Arc<dyn Observer>);
+
+        game.play_hand();
+
+        let seen = player.0.lock().unwrap();
+        let hole_cards: Vec<usize> = seen
+            .iter()
+            .filter_map(|event| match event {
+                Event::HoleCards { seat, .. } => Some(*seat),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(hole_cards, vec![0]);
+        assert!(matches!(seen[0], Event::HandStarted { .. }));
+        assert!(seen.contains(&Event::StreetStarted { street: "River" }));
+        assert!(seen.iter().any(|e| matches!(e, Event::PotWon { .. })));
+
+        // the observer gets everything but the hole cards
+        let watched = observer.0.lock().unwrap();
+        assert_eq!(watched.len(), seen.len() - 1);
+        assert!(watched.iter().all(|event| event.is_public()));
+        assert!(watched.iter().any(|e| matches!(e, Event::Shown { .. })));
+
+        // the table keeps HUD stats from the same events
+        let alice = game.hud.stats("Alice").unwrap();
+        assert_eq!(alice.hands, 1);
+        assert_eq!(alice.went_to_showdown.count, 1);
+    }
+
+    /// Nobody busts for a while at a table where everybody calls
+    fn table_with_callers(names: &[&str]) -> TexasHoldem {
+        let mut game = TexasHoldem::new(4, 2000, 20000);
+        for &name in names {
+            game.add_player(name.to_string(), Box::new(Recorder::default()));
+        }
+        game
+    }
+
+    #[test]
+    fn test_other_games() {
+        let variants: Vec<(Arc<dyn Variant>, usize)> = vec![
+            (Arc::new(FiveCardDraw::default()), 6),
+            (Arc::new(SevenCardStud::default()), 8),
+            (Arc::new(DeuceToSeven::triple_draw()), 6),
+            (Arc::new(DeuceToSeven::single_draw()), 6),
+            (Arc::new(Badugi::default()), 8),
+            (Arc::new(Pineapple::default()), 10),
+            (Arc::new(Omaha::default().double_board()), 10),
+            (Arc::new(Courchevel::default()), 9),
+            (Arc::new(Irish::default()), 10),
+        ];
+
+        for (variant, seats) in variants {
+            let mut game = Table::with_variant(variant, 10, 2000, 20000);
+            assert_eq!(game.seats, seats);
+
+            for name in ["Alice", "Bob"] {
+                game.add_player(name.to_string(), Box::new(NPC::new(name.to_string())));
+            }
+            game.add_player(
+                "Charlie".to_string(),
+                Box::new(
+                    MonteCarlo::new("Charlie".to_string())
+                        .with_simulations(20)
+                        .weighted(),
+                ),
+            );
+
+            for _ in 0..3 {
+                game.play_hand();
+            }
+
+            assert_eq!(game.players.iter().map(|p| p.chips).sum::<usize>(), 60000);
+        }
+    }
+
+    #[test]
+    fn test_mixed_rotation() {
+        let mut rotation = Rotation::horse(2000);
+        rotation.period = Period::Hands(2);
+        let mut game = Table::with_rotation(rotation, 10, 20000);
+        assert_eq!(game.seats, 8);
+
+        for name in ["Alice", "Bob", "Charlie"] {
+            game.add_player(name.to_string(), Box::new(NPC::new(name.to_string())));
+        }
+
+        let mut played = vec![];
+        for _ in 0..6 {
+            game.play_hand();
+            played.push(game.variant.game_type());
+        }
+
+        assert_eq!(
+            played,
+            vec![
+                GameType::TexasHoldem,
+                GameType::TexasHoldem,
+                GameType::OmahaHiLo,
+                GameType::OmahaHiLo,
+                GameType::Razz,
+                GameType::Razz
+            ]
         );
+        assert_eq!(game.players.iter().map(|p| p.chips).sum::<usize>(), 60000);
+    }
+
+    struct Chooser(usize);
+    impl Actionable for Chooser {
+        fn action(&self, _game: &GameContext) -> PlayerAction {
+            PlayerAction::Call
+        }
+
+        fn choose_game(&self, _games: &[String]) -> usize {
+            self.0
+        }
+    }
 
-        game.play();
+    #[test]
+    fn test_dealers_choice() {
+        let mut rotation = GameType::DealerChoice.rotation(2000).unwrap();
+        rotation.period = Period::Hands(1);
+        let mut game = Table::with_rotation(rotation, 10, 20000);
+
+        // the button picks Razz, an illegal pick falls back to the next game in order
+        game.add_player("Alice".to_string(), Box::new(Chooser(3)));
+        game.add_player("Bob".to_string(), Box::new(Chooser(99)));
+
+        game.play_hand();
+        assert_eq!(game.variant.game_type(), GameType::Razz);
+        game.play_hand();
+        assert_eq!(game.variant.game_type(), GameType::SevenCardStud);
+    }
+
+    #[test]
+    fn test_sitting_out_misses_blinds() {
+        let mut game = table_with_callers(&["Alice", "Bob", "Charlie", "David"]);
+
+        game.play_hand();
+        game.sit_out(3).unwrap();
+        let chips = game.players[3].chips;
+        game.play_hand();
+
+        // the big blind went past David's seat
+        assert_eq!(game.players[3].owed_blinds, OwedBlinds::Both);
+        assert_eq!(game.players[3].chips, chips);
+
+        game.sit_in(3).unwrap();
+        game.play_hand();
+        assert_eq!(game.players[3].owed_blinds, OwedBlinds::None);
+    }
+
+    #[test]
+    fn test_seat_changes_between_hands() {
+        let mut game = table_with_callers(&["Alice", "Bob", "Charlie", "David"]);
+        game.max_buy_in = 30000;
+
+        assert_eq!(
+            game.join("Eve".to_string(), Box::new(NPC::new("Eve".to_string()))),
+            Err(SeatError::TableFull)
+        );
+        assert_eq!(game.rebuy(0), Err(SeatError::NotBusted { seat: 0 }));
+        assert_eq!(game.add_on(0), Err(SeatError::NoAddOn));
+
+        game.play_hand();
+
+        let leaving = game.leave(1).unwrap();
+        assert_eq!(leaving.name, "Bob");
+        assert_eq!(game.leave(1).unwrap_err(), SeatError::EmptySeat { seat: 1 });
+
+        // Eve takes the free seat and owes a big blind
+        let seat = game
+            .join("Eve".to_string(), Box::new(Recorder::default()))
+            .unwrap();
+        assert_eq!(seat, 1);
+        assert_eq!(game.players[1].owed_blinds, OwedBlinds::BigBlind);
+
+        let removed = game.players[2].chips;
+        game.players[2].chips = 0;
+        game.players[2].eliminated = true;
+        game.rebuy(2).unwrap();
+        assert_eq!(game.players[2].chips, 20000);
+
+        let chips = game.players[3].chips;
+        assert_eq!(
+            game.top_up(3, 30000 - chips + 1),
+            Err(SeatError::AboveMaximum { maximum: 30000 })
+        );
+        game.top_up(3, 30000 - chips).unwrap();
+
+        for _ in 0..5 {
+            game.play_hand();
+        }
+
+        // apart from the chips taken away above, chips only moved between players
+        let balances = game.balances();
+        assert_eq!(balances.len(), 5);
+        assert_eq!(
+            balances.iter().map(|(_, b)| b).sum::<isize>(),
+            -(removed as isize)
+        );
     }
 }
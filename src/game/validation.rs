@@ -0,0 +1,146 @@
+use std::fmt::Display;
+
+use super::actor::{Discard, PlayerAction};
+
+/// Reasons for the table to refuse a `PlayerAction`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IllegalAction {
+    /// The bet or raise does not go to the minimum and is not an all in
+    RaiseBelowMinimum { amount: usize, minimum: usize },
+    /// The bet or raise goes to more than the player has
+    BetAboveStack { amount: usize, maximum: usize },
+    /// The bet or raise goes over what the betting structure allows
+    RaiseAboveMaximum { amount: usize, maximum: usize },
+    /// Betting is capped or nobody is left to raise against
+    CannotRaise,
+    /// Checking is only possible when there is nothing to call
+    CheckFacingBet { call_amount: usize },
+    /// There is a bet already, it can only be raised
+    BetFacingBet { current_bet: usize },
+    /// Nobody bet yet, so there is nothing to raise
+    NothingToRaise,
+    /// Another seat has to act first
+    OutOfTurn { seat: usize, to_act: Option<usize> },
+    /// The player already folded this hand
+    AlreadyFolded { seat: usize },
+    /// Nobody plays on this seat in this hand
+    NoSuchSeat { seat: usize },
+    /// The hand is waiting for a different step
+    OutOfOrder,
+    /// There are not enough cards left to deal
+    DeckExhausted,
+    /// The draw allows fewer discards
+    TooManyDiscards { count: usize, maximum: usize },
+    /// More cards have to be thrown away
+    TooFewDiscards { count: usize, required: usize },
+    /// The discard points at a position past the end of the hand
+    NoSuchCard { position: usize },
+}
+
+impl Display for IllegalAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IllegalAction::RaiseBelowMinimum { amount, minimum } => {
+                write!(f, "raise to {} is below the minimum of {}", amount, minimum)
+            }
+            IllegalAction::BetAboveStack { amount, maximum } => {
+                write!(
+                    f,
+                    "bet to {} is more than the {} chips available",
+                    amount, maximum
+                )
+            }
+            IllegalAction::RaiseAboveMaximum { amount, maximum } => {
+                write!(f, "raise to {} is above the maximum of {}", amount, maximum)
+            }
+            IllegalAction::CannotRaise => write!(f, "can't raise any further"),
+            IllegalAction::CheckFacingBet { call_amount } => {
+                write!(f, "can't check, {} to call", call_amount)
+            }
+            IllegalAction::BetFacingBet { current_bet } => {
+                write!(f, "can't bet, there is a bet of {} to raise", current_bet)
+            }
+            IllegalAction::NothingToRaise => write!(f, "can't raise, nobody bet yet"),
+            IllegalAction::OutOfTurn { seat, to_act } => match to_act {
+                Some(to_act) => write!(f, "seat {} acted but it is seat {}'s turn", seat, to_act),
+                None => write!(f, "seat {} acted but nobody is to act", seat),
+            },
+            IllegalAction::AlreadyFolded { seat } => write!(f, "seat {} already folded", seat),
+            IllegalAction::NoSuchSeat { seat } => write!(f, "there is no seat {}", seat),
+            IllegalAction::OutOfOrder => write!(f, "the hand is waiting for something else"),
+            IllegalAction::DeckExhausted => write!(f, "the deck is empty"),
+            IllegalAction::TooManyDiscards { count, maximum } => {
+                write!(f, "can't discard {} cards, {} at most", count, maximum)
+            }
+            IllegalAction::TooFewDiscards { count, required } => {
+                write!(
+                    f,
+                    "can't discard {} cards, {} are required",
+                    count, required
+                )
+            }
+            IllegalAction::NoSuchCard { position } => {
+                write!(f, "there is no card at position {}", position)
+            }
+        }
+    }
+}
+
+impl std::error::Error for IllegalAction {}
+
+/// What the table does when an actor comes up with an illegal action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IllegalActionPolicy {
+    /// Ask the actor again. Gives up and folds after `MAX_REPROMPTS` attempts
+    Reprompt,
+    /// Fold the hand
+    #[default]
+    Fold,
+    /// Check or call instead
+    Call,
+}
+
+/// Attempts an actor with the `Reprompt` policy gets before the hand is folded for it
+pub const MAX_REPROMPTS: usize = 3;
+
+impl IllegalActionPolicy {
+    /// Replacement for an illegal action, `None` if the actor should be asked again
+    pub fn coerce(&self, attempt: usize) -> Option<PlayerAction> {
+        match self {
+            IllegalActionPolicy::Reprompt if attempt < MAX_REPROMPTS => None,
+            IllegalActionPolicy::Reprompt | IllegalActionPolicy::Fold => Some(PlayerAction::Fold),
+            IllegalActionPolicy::Call => Some(PlayerAction::Call),
+        }
+    }
+
+    /// Replacement for an illegal discard, every policy but a reprompt goes with `fallback`
+    pub fn coerce_discard(&self, attempt: usize, fallback: Discard) -> Option<Discard> {
+        match self {
+            IllegalActionPolicy::Reprompt if attempt < MAX_REPROMPTS => None,
+            _ => Some(fallback),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_coerces_illegal_actions() {
+        assert_eq!(
+            IllegalActionPolicy::Fold.coerce(1),
+            Some(PlayerAction::Fold)
+        );
+        assert_eq!(
+            IllegalActionPolicy::Call.coerce(1),
+            Some(PlayerAction::Call)
+        );
+
+        assert_eq!(IllegalActionPolicy::Reprompt.coerce(1), None);
+        assert_eq!(
+            IllegalActionPolicy::Reprompt.coerce(MAX_REPROMPTS),
+            Some(PlayerAction::Fold)
+        );
+    }
+}
@@ -0,0 +1,238 @@
+use std::fmt::{Debug, Display};
+
+use crate::{
+    cards::rank::{self, Rank},
+    get_suit, get_value, Card, CLUB, DIAMOND, HEART, SPADE,
+};
+
+use super::settings::GameType;
+
+/// Whether the other players get to see a hole card
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Face {
+    Down,
+    Up,
+}
+
+/// Cards dealt at the start of a street
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Deal {
+    /// Cards to every player that is still in the hand, one at a time
+    Hole { count: usize, face: Face },
+    /// Cards to the board, shared by everyone
+    Community { count: usize },
+    /// Every player still in the hand discards up to `max` cards and draws replacements,
+    /// starting left of the button
+    Draw { max: usize },
+    /// Every player still in the hand throws away exactly `count` cards, they are dead
+    Discard { count: usize },
+}
+
+/// A round of dealing followed by a round of betting
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Street {
+    pub name: &'static str,
+    pub deals: Vec<Deal>,
+    /// Fixed limit games bet twice the big blind on this street
+    pub big_bet: bool,
+}
+
+impl Street {
+    pub fn new(name: &'static str, deals: Vec<Deal>) -> Self {
+        Self {
+            name,
+            deals,
+            big_bet: false,
+        }
+    }
+
+    /// Hole and community cards dealt on the street
+    pub fn card_counts(&self) -> (usize, usize) {
+        self.deals
+            .iter()
+            .fold((0, 0), |(hole, community), deal| match deal {
+                Deal::Hole { count, .. } => (hole + count, community),
+                Deal::Community { count } => (hole, community + count),
+                Deal::Draw { .. } | Deal::Discard { .. } => (hole, community),
+            })
+    }
+
+    /// Marks the street as one where fixed limit games use the big bet
+    pub fn big_bet(mut self) -> Self {
+        self.big_bet = true;
+        self
+    }
+}
+
+/// How much a player can bet or raise
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BettingStructure {
+    /// Anything from a minimum raise up to the whole stack
+    #[default]
+    NoLimit,
+    /// Anything from a minimum raise up to the size of the pot
+    PotLimit,
+    /// Bets and raises of one fixed size, capped at a bet and three raises per street
+    FixedLimit,
+}
+
+impl Display for BettingStructure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            BettingStructure::NoLimit => "No Limit",
+            BettingStructure::PotLimit => "Pot Limit",
+            BettingStructure::FixedLimit => "Limit",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Bets that have to be posted before the cards are dealt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ForcedBets {
+    /// Small and big blind left of the button
+    #[default]
+    Blinds,
+    /// Everybody antes, then the weakest door card brings it in.
+    /// Both amounts are a percentage of the small bet
+    BringIn { ante: usize, bring_in: usize },
+}
+
+/// Which hands win at showdown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Evaluator {
+    /// Best five card poker hand
+    #[default]
+    High,
+    /// Best five card poker hand with exactly two hole cards and three from the board
+    Omaha,
+    /// Best five card ace-to-five low, pairs are bad and straights and flushes don't count
+    Low,
+    /// Worst five card poker hand, aces are high and straights and flushes count against it
+    DeuceToSeven,
+    /// Half the pot for the best high, the other half for the best eight or better low
+    HiLo,
+    /// Omaha hands for both halves of a hi-lo split
+    OmahaHiLo,
+    /// Most cards of different suits and values, then the lowest
+    Badugi,
+}
+
+/// Strength of a player's cards at showdown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandValue {
+    /// Wins the pot, or the high half of it in split games
+    pub rank: Rank,
+    /// Qualifying low that wins the other half of a split pot
+    pub low: Option<Rank>,
+}
+
+impl Evaluator {
+    pub fn evaluate(&self, hole: &[Card], board: &[Card]) -> HandValue {
+        let cards: Vec<Card> = hole.iter().chain(board.iter()).copied().collect();
+
+        match self {
+            Evaluator::High => HandValue {
+                rank: rank::high(&cards),
+                low: None,
+            },
+            Evaluator::Omaha => HandValue {
+                rank: rank::omaha(hole, board),
+                low: None,
+            },
+            Evaluator::Low => HandValue {
+                rank: rank::ace_to_five_low(&cards),
+                low: None,
+            },
+            Evaluator::DeuceToSeven => HandValue {
+                rank: rank::deuce_to_seven_low(&cards),
+                low: None,
+            },
+            Evaluator::HiLo => HandValue {
+                rank: rank::high(&cards),
+                low: rank::eight_or_better(&cards),
+            },
+            Evaluator::OmahaHiLo => HandValue {
+                rank: rank::omaha(hole, board),
+                low: rank::omaha_eight_or_better(hole, board),
+            },
+            Evaluator::Badugi => HandValue {
+                rank: rank::badugi(&cards),
+                low: None,
+            },
+        }
+    }
+}
+
+/// Rules of a poker game: what gets dealt when, how betting works and who wins.
+///
+/// The hand state machine only knows about streets, deals and bets, everything
+/// specific to a game comes from its variant.
+pub trait Variant: Send + Sync {
+    fn game_type(&self) -> GameType;
+
+    fn name(&self) -> String;
+
+    /// Streets in the order they are played, the first one starts with the forced bets
+    fn streets(&self) -> Vec<Street>;
+
+    fn betting(&self) -> BettingStructure;
+
+    fn forced_bets(&self) -> ForcedBets {
+        ForcedBets::default()
+    }
+
+    fn evaluator(&self) -> Evaluator;
+
+    /// Community boards dealt side by side, every pot is shared between them
+    fn boards(&self) -> usize {
+        1
+    }
+
+    /// What the hole cards are worth together with the board at showdown
+    fn evaluate(&self, hole: &[Card], board: &[Card]) -> HandValue {
+        self.evaluator().evaluate(hole, board)
+    }
+
+    /// Orders door cards for the bring-in, the lowest card pays it.
+    /// Ties in value go by suit, from clubs up to spades
+    fn bring_in_order(&self, card: Card) -> u32 {
+        get_value(card).trailing_zeros() * 4 + suit_order(card)
+    }
+
+    /// Strength of the face up cards, the strongest board starts the betting on later streets
+    fn visible_rank(&self, up: &[Card]) -> Rank {
+        rank::high(up)
+    }
+
+    /// Hole cards every player gets and community cards on every board over the whole hand
+    fn card_counts(&self) -> (usize, usize) {
+        self.streets()
+            .iter()
+            .map(Street::card_counts)
+            .fold((0, 0), |(hole, community), (h, c)| {
+                (hole + h, community + c)
+            })
+    }
+
+    /// Most players a single deck can be dealt to
+    fn max_players(&self) -> usize {
+        let (hole, community) = self.card_counts();
+
+        (52 - community * self.boards()) / hole.max(1)
+    }
+}
+
+/// Suits from clubs to spades, for breaking ties between cards of the same value
+pub fn suit_order(card: Card) -> u32 {
+    [CLUB, DIAMOND, HEART, SPADE]
+        .iter()
+        .position(|&s| s == get_suit(card))
+        .unwrap_or(0) as u32
+}
+
+impl Debug for dyn Variant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name())
+    }
+}
@@ -0,0 +1,213 @@
+use crate::game::{
+    settings::GameType,
+    variant::{BettingStructure, Deal, Evaluator, Face, Street, Variant},
+};
+
+/// Five hole cards, one draw and a high hand showdown
+#[derive(Debug, Clone, Copy)]
+pub struct FiveCardDraw {
+    pub betting: BettingStructure,
+    /// Most cards a player can exchange in the draw
+    pub max_draw: usize,
+}
+
+impl Default for FiveCardDraw {
+    fn default() -> Self {
+        Self {
+            betting: BettingStructure::FixedLimit,
+            max_draw: 5,
+        }
+    }
+}
+
+impl Variant for FiveCardDraw {
+    fn game_type(&self) -> GameType {
+        GameType::FiveCardDraw
+    }
+
+    fn name(&self) -> String {
+        "Five Card Draw".to_string()
+    }
+
+    fn streets(&self) -> Vec<Street> {
+        vec![
+            Street::new(
+                "Before the draw",
+                vec![Deal::Hole {
+                    count: 5,
+                    face: Face::Down,
+                }],
+            ),
+            Street::new("After the draw", vec![Deal::Draw { max: self.max_draw }]).big_bet(),
+        ]
+    }
+
+    fn betting(&self) -> BettingStructure {
+        self.betting
+    }
+
+    fn evaluator(&self) -> Evaluator {
+        Evaluator::High
+    }
+
+    /// Leaves enough cards for the draw with the discards shuffled in
+    fn max_players(&self) -> usize {
+        6
+    }
+}
+
+/// Deuce-to-seven lowball with one or three draws, the worst high hand wins
+#[derive(Debug, Clone, Copy)]
+pub struct DeuceToSeven {
+    pub draws: usize,
+    pub betting: BettingStructure,
+}
+
+impl DeuceToSeven {
+    /// Limit with three draws
+    pub fn triple_draw() -> Self {
+        Self {
+            draws: 3,
+            betting: BettingStructure::FixedLimit,
+        }
+    }
+
+    /// No limit with a single draw
+    pub fn single_draw() -> Self {
+        Self {
+            draws: 1,
+            betting: BettingStructure::NoLimit,
+        }
+    }
+}
+
+impl Variant for DeuceToSeven {
+    fn game_type(&self) -> GameType {
+        match self.draws {
+            1 => GameType::SingleDraw,
+            _ => GameType::TripleDraw,
+        }
+    }
+
+    fn name(&self) -> String {
+        match self.draws {
+            1 => format!("{} 2-7 Single Draw", self.betting),
+            _ => format!("{} 2-7 Triple Draw", self.betting),
+        }
+    }
+
+    fn streets(&self) -> Vec<Street> {
+        draw_streets(self.draws, 5)
+    }
+
+    fn betting(&self) -> BettingStructure {
+        self.betting
+    }
+
+    fn evaluator(&self) -> Evaluator {
+        Evaluator::DeuceToSeven
+    }
+
+    /// Leaves enough cards for the draws with the discards shuffled in
+    fn max_players(&self) -> usize {
+        6
+    }
+}
+
+/// Limit triple draw with four cards, the best badugi wins
+#[derive(Debug, Clone, Copy)]
+pub struct Badugi {
+    pub betting: BettingStructure,
+}
+
+impl Default for Badugi {
+    fn default() -> Self {
+        Self {
+            betting: BettingStructure::FixedLimit,
+        }
+    }
+}
+
+impl Variant for Badugi {
+    fn game_type(&self) -> GameType {
+        GameType::Badugi
+    }
+
+    fn name(&self) -> String {
+        "Badugi".to_string()
+    }
+
+    fn streets(&self) -> Vec<Street> {
+        draw_streets(3, 4)
+    }
+
+    fn betting(&self) -> BettingStructure {
+        self.betting
+    }
+
+    fn evaluator(&self) -> Evaluator {
+        Evaluator::Badugi
+    }
+
+    /// Leaves enough cards for the draws with the discards shuffled in
+    fn max_players(&self) -> usize {
+        8
+    }
+}
+
+/// A deal of `cards` face down and `draws` rounds of drawing up to all of them,
+/// the second half of the draws plays for the big bet
+pub fn draw_streets(draws: usize, cards: usize) -> Vec<Street> {
+    const AFTER: [&str; 3] = [
+        "After the first draw",
+        "After the second draw",
+        "After the third draw",
+    ];
+
+    let mut streets = vec![Street::new(
+        "Before the draw",
+        vec![Deal::Hole {
+            count: cards,
+            face: Face::Down,
+        }],
+    )];
+
+    for draw in 0..draws {
+        let name = match draws {
+            1 => "After the draw",
+            _ => AFTER[draw.min(AFTER.len() - 1)],
+        };
+        let street = Street::new(name, vec![Deal::Draw { max: cards }]);
+
+        streets.push(match draw + 1 >= draws.div_ceil(2) {
+            true => street.big_bet(),
+            false => street,
+        });
+    }
+
+    streets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draw_streets() {
+        let triple = DeuceToSeven::triple_draw().streets();
+        assert_eq!(triple.len(), 4);
+        assert_eq!(
+            triple.iter().map(|s| s.big_bet).collect::<Vec<_>>(),
+            vec![false, false, true, true]
+        );
+
+        let single = DeuceToSeven::single_draw().streets();
+        assert_eq!(single[1].name, "After the draw");
+        assert!(single[1].big_bet);
+        assert_eq!(single, FiveCardDraw::default().streets());
+
+        let badugi = Badugi::default().streets();
+        assert_eq!(badugi.len(), 4);
+        assert_eq!(badugi[3].deals, vec![Deal::Draw { max: 4 }]);
+    }
+}
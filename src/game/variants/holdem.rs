@@ -0,0 +1,80 @@
+use crate::game::{
+    settings::GameType,
+    variant::{BettingStructure, Deal, Evaluator, Face, Street, Variant},
+};
+
+/// Two hole cards and five community cards
+#[derive(Debug, Clone, Copy)]
+pub struct Holdem {
+    pub betting: BettingStructure,
+    pub boards: usize,
+}
+
+impl Holdem {
+    pub fn new(betting: BettingStructure) -> Self {
+        Self { betting, boards: 1 }
+    }
+
+    /// Deals two boards, each one plays for half of every pot
+    pub fn double_board(mut self) -> Self {
+        self.boards = 2;
+        self
+    }
+}
+
+impl Default for Holdem {
+    fn default() -> Self {
+        Self::new(BettingStructure::NoLimit)
+    }
+}
+
+impl Variant for Holdem {
+    fn game_type(&self) -> GameType {
+        GameType::TexasHoldem
+    }
+
+    fn name(&self) -> String {
+        match self.boards {
+            1 => format!("{} Texas Hold'em", self.betting),
+            _ => format!("Double Board {} Texas Hold'em", self.betting),
+        }
+    }
+
+    fn streets(&self) -> Vec<Street> {
+        vec![
+            Street::new(
+                "Preflop",
+                vec![Deal::Hole {
+                    count: 2,
+                    face: Face::Down,
+                }],
+            ),
+            Street::new("Flop", vec![Deal::Community { count: 3 }]),
+            Street::new("Turn", vec![Deal::Community { count: 1 }]).big_bet(),
+            Street::new("River", vec![Deal::Community { count: 1 }]).big_bet(),
+        ]
+    }
+
+    fn betting(&self) -> BettingStructure {
+        self.betting
+    }
+
+    fn evaluator(&self) -> Evaluator {
+        Evaluator::High
+    }
+
+    fn boards(&self) -> usize {
+        self.boards
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_players() {
+        assert_eq!(Holdem::default().max_players(), 23);
+        assert_eq!(Holdem::default().double_board().max_players(), 21);
+    }
+}
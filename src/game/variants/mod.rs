@@ -0,0 +1,11 @@
+pub mod draw;
+pub mod holdem;
+pub mod omaha;
+pub mod pineapple;
+pub mod stud;
+
+pub use draw::{Badugi, DeuceToSeven, FiveCardDraw};
+pub use holdem::Holdem;
+pub use omaha::{Courchevel, Omaha};
+pub use pineapple::{CrazyPineapple, Irish, Pineapple};
+pub use stud::{Razz, SevenCardStud, StudHiLo};
@@ -0,0 +1,181 @@
+use crate::game::{
+    settings::GameType,
+    variant::{BettingStructure, Deal, Evaluator, Face, Street, Variant},
+};
+
+/// Four, five or six hole cards and five community cards,
+/// hands are made from exactly two hole cards and three from the board
+#[derive(Debug, Clone, Copy)]
+pub struct Omaha {
+    pub hole_cards: usize,
+    pub betting: BettingStructure,
+    pub boards: usize,
+    /// Splits every pot with the best eight or better low
+    pub hi_lo: bool,
+}
+
+impl Omaha {
+    /// Pot limit Omaha with `hole_cards` cards
+    pub fn new(hole_cards: usize) -> Self {
+        Self {
+            hole_cards,
+            betting: BettingStructure::PotLimit,
+            boards: 1,
+            hi_lo: false,
+        }
+    }
+
+    /// Omaha eight or better, half of every pot goes to the best low
+    pub fn hi_lo(mut self) -> Self {
+        self.hi_lo = true;
+        self
+    }
+
+    pub fn with_betting(mut self, betting: BettingStructure) -> Self {
+        self.betting = betting;
+        self
+    }
+
+    /// Deals two boards, each one plays for half of every pot
+    pub fn double_board(mut self) -> Self {
+        self.boards = 2;
+        self
+    }
+}
+
+impl Default for Omaha {
+    fn default() -> Self {
+        Self::new(4)
+    }
+}
+
+impl Variant for Omaha {
+    fn game_type(&self) -> GameType {
+        match (self.boards, self.hi_lo) {
+            (1, false) => GameType::Omaha,
+            (1, true) => GameType::OmahaHiLo,
+            _ => GameType::DoubleBoardOmaha,
+        }
+    }
+
+    fn name(&self) -> String {
+        let mut name = match self.hole_cards {
+            4 => format!("{} Omaha", self.betting),
+            cards => format!("{} {} Card Omaha", self.betting, cards),
+        };
+        if self.hi_lo {
+            name.push_str(" Hi-Lo");
+        }
+
+        match self.boards {
+            1 => name,
+            _ => format!("Double Board {}", name),
+        }
+    }
+
+    fn streets(&self) -> Vec<Street> {
+        vec![
+            Street::new(
+                "Preflop",
+                vec![Deal::Hole {
+                    count: self.hole_cards,
+                    face: Face::Down,
+                }],
+            ),
+            Street::new("Flop", vec![Deal::Community { count: 3 }]),
+            Street::new("Turn", vec![Deal::Community { count: 1 }]).big_bet(),
+            Street::new("River", vec![Deal::Community { count: 1 }]).big_bet(),
+        ]
+    }
+
+    fn betting(&self) -> BettingStructure {
+        self.betting
+    }
+
+    fn evaluator(&self) -> Evaluator {
+        match self.hi_lo {
+            true => Evaluator::OmahaHiLo,
+            false => Evaluator::Omaha,
+        }
+    }
+
+    fn boards(&self) -> usize {
+        self.boards
+    }
+}
+
+/// Five card Omaha where the first card of the flop is dealt before the first betting round
+#[derive(Debug, Clone, Copy)]
+pub struct Courchevel {
+    pub betting: BettingStructure,
+}
+
+impl Default for Courchevel {
+    fn default() -> Self {
+        Self {
+            betting: BettingStructure::PotLimit,
+        }
+    }
+}
+
+impl Variant for Courchevel {
+    fn game_type(&self) -> GameType {
+        GameType::Courchevel
+    }
+
+    fn name(&self) -> String {
+        "Courchevel".to_string()
+    }
+
+    fn streets(&self) -> Vec<Street> {
+        vec![
+            Street::new(
+                "Preflop",
+                vec![
+                    Deal::Hole {
+                        count: 5,
+                        face: Face::Down,
+                    },
+                    Deal::Community { count: 1 },
+                ],
+            ),
+            Street::new("Flop", vec![Deal::Community { count: 2 }]),
+            Street::new("Turn", vec![Deal::Community { count: 1 }]).big_bet(),
+            Street::new("River", vec![Deal::Community { count: 1 }]).big_bet(),
+        ]
+    }
+
+    fn betting(&self) -> BettingStructure {
+        self.betting
+    }
+
+    fn evaluator(&self) -> Evaluator {
+        Evaluator::Omaha
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hole_cards_and_seats() {
+        assert_eq!(Omaha::default().max_players(), 11);
+        assert_eq!(Omaha::new(5).max_players(), 9);
+        assert_eq!(Omaha::new(6).max_players(), 7);
+        assert_eq!(Omaha::new(6).name(), "Pot Limit 6 Card Omaha");
+        assert_eq!(Omaha::default().double_board().max_players(), 10);
+        assert_eq!(
+            Omaha::default().double_board().name(),
+            "Double Board Pot Limit Omaha"
+        );
+        assert_eq!(Courchevel::default().max_players(), 9);
+        assert_eq!(
+            Omaha::default()
+                .hi_lo()
+                .with_betting(BettingStructure::FixedLimit)
+                .name(),
+            "Limit Omaha Hi-Lo"
+        );
+    }
+}
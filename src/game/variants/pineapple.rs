@@ -0,0 +1,131 @@
+use crate::game::{
+    settings::GameType,
+    variant::{BettingStructure, Deal, Evaluator, Face, Street, Variant},
+};
+
+fn preflop() -> Street {
+    Street::new(
+        "Preflop",
+        vec![Deal::Hole {
+            count: 3,
+            face: Face::Down,
+        }],
+    )
+}
+
+/// Hold'em with three hole cards, one of them is thrown away before the flop
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Pineapple {
+    pub betting: BettingStructure,
+}
+
+impl Variant for Pineapple {
+    fn game_type(&self) -> GameType {
+        GameType::Pineapple
+    }
+
+    fn name(&self) -> String {
+        "Pineapple".to_string()
+    }
+
+    fn streets(&self) -> Vec<Street> {
+        vec![
+            preflop(),
+            Street::new(
+                "Flop",
+                vec![Deal::Discard { count: 1 }, Deal::Community { count: 3 }],
+            ),
+            Street::new("Turn", vec![Deal::Community { count: 1 }]).big_bet(),
+            Street::new("River", vec![Deal::Community { count: 1 }]).big_bet(),
+        ]
+    }
+
+    fn betting(&self) -> BettingStructure {
+        self.betting
+    }
+
+    fn evaluator(&self) -> Evaluator {
+        Evaluator::High
+    }
+}
+
+/// Pineapple where the extra card is kept until the betting on the flop is done
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CrazyPineapple {
+    pub betting: BettingStructure,
+}
+
+impl Variant for CrazyPineapple {
+    fn game_type(&self) -> GameType {
+        GameType::CrazyPineapple
+    }
+
+    fn name(&self) -> String {
+        "Crazy Pineapple".to_string()
+    }
+
+    fn streets(&self) -> Vec<Street> {
+        vec![
+            preflop(),
+            Street::new("Flop", vec![Deal::Community { count: 3 }]),
+            Street::new(
+                "Turn",
+                vec![Deal::Discard { count: 1 }, Deal::Community { count: 1 }],
+            )
+            .big_bet(),
+            Street::new("River", vec![Deal::Community { count: 1 }]).big_bet(),
+        ]
+    }
+
+    fn betting(&self) -> BettingStructure {
+        self.betting
+    }
+
+    fn evaluator(&self) -> Evaluator {
+        Evaluator::High
+    }
+}
+
+/// Four hole cards like Omaha, two of them are thrown away after the betting on the flop
+/// and the hand plays like hold'em from there
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Irish {
+    pub betting: BettingStructure,
+}
+
+impl Variant for Irish {
+    fn game_type(&self) -> GameType {
+        GameType::Irish
+    }
+
+    fn name(&self) -> String {
+        "Irish".to_string()
+    }
+
+    fn streets(&self) -> Vec<Street> {
+        vec![
+            Street::new(
+                "Preflop",
+                vec![Deal::Hole {
+                    count: 4,
+                    face: Face::Down,
+                }],
+            ),
+            Street::new("Flop", vec![Deal::Community { count: 3 }]),
+            Street::new(
+                "Turn",
+                vec![Deal::Discard { count: 2 }, Deal::Community { count: 1 }],
+            )
+            .big_bet(),
+            Street::new("River", vec![Deal::Community { count: 1 }]).big_bet(),
+        ]
+    }
+
+    fn betting(&self) -> BettingStructure {
+        self.betting
+    }
+
+    fn evaluator(&self) -> Evaluator {
+        Evaluator::High
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{
+    cards::rank::{self, Rank},
+    game::{
+        settings::GameType,
+        variant::{
+            suit_order, BettingStructure, Deal, Evaluator, Face, ForcedBets, Street, Variant,
+        },
+    },
+    get_value, Card,
+};
+
+/// Two down and one up on third street, one up on each of the next three streets
+/// and the last card down
+#[derive(Debug, Clone, Copy)]
+pub struct SevenCardStud {
+    pub betting: BettingStructure,
+}
+
+impl Default for SevenCardStud {
+    fn default() -> Self {
+        Self {
+            betting: BettingStructure::FixedLimit,
+        }
+    }
+}
+
+/// Streets shared by all seven card stud games
+pub fn stud_streets() -> Vec<Street> {
+    let up = |count| Deal::Hole {
+        count,
+        face: Face::Up,
+    };
+
+    vec![
+        Street::new(
+            "Third street",
+            vec![
+                Deal::Hole {
+                    count: 2,
+                    face: Face::Down,
+                },
+                up(1),
+            ],
+        ),
+        Street::new("Fourth street", vec![up(1)]),
+        Street::new("Fifth street", vec![up(1)]).big_bet(),
+        Street::new("Sixth street", vec![up(1)]).big_bet(),
+        Street::new(
+            "Seventh street",
+            vec![Deal::Hole {
+                count: 1,
+                face: Face::Down,
+            }],
+        )
+        .big_bet(),
+    ]
+}
+
+/// Ante of a tenth and a bring-in of a quarter of the small bet
+pub const STUD_FORCED_BETS: ForcedBets = ForcedBets::BringIn {
+    ante: 10,
+    bring_in: 25,
+};
+
+impl Variant for SevenCardStud {
+    fn game_type(&self) -> GameType {
+        GameType::SevenCardStud
+    }
+
+    fn name(&self) -> String {
+        "Seven Card Stud".to_string()
+    }
+
+    fn streets(&self) -> Vec<Street> {
+        stud_streets()
+    }
+
+    fn betting(&self) -> BettingStructure {
+        self.betting
+    }
+
+    fn forced_bets(&self) -> ForcedBets {
+        STUD_FORCED_BETS
+    }
+
+    fn evaluator(&self) -> Evaluator {
+        Evaluator::High
+    }
+
+    /// A community card replaces the last card when eight players stay until seventh street
+    fn max_players(&self) -> usize {
+        8
+    }
+}
+
+/// Seven card stud for the lowest hand, aces are low and straights and flushes don't count
+#[derive(Debug, Clone, Copy)]
+pub struct Razz {
+    pub betting: BettingStructure,
+}
+
+impl Default for Razz {
+    fn default() -> Self {
+        Self {
+            betting: BettingStructure::FixedLimit,
+        }
+    }
+}
+
+impl Variant for Razz {
+    fn game_type(&self) -> GameType {
+        GameType::Razz
+    }
+
+    fn name(&self) -> String {
+        "Razz".to_string()
+    }
+
+    fn streets(&self) -> Vec<Street> {
+        stud_streets()
+    }
+
+    fn betting(&self) -> BettingStructure {
+        self.betting
+    }
+
+    fn forced_bets(&self) -> ForcedBets {
+        STUD_FORCED_BETS
+    }
+
+    fn evaluator(&self) -> Evaluator {
+        Evaluator::Low
+    }
+
+    /// The highest door card brings it in, kings are the highest and spades break ties
+    fn bring_in_order(&self, card: Card) -> u32 {
+        let value = match get_value(card).trailing_zeros() {
+            13 => 0,
+            value => value,
+        };
+
+        (13 - value) * 4 + (3 - suit_order(card))
+    }
+
+    /// The best low board starts the betting
+    fn visible_rank(&self, up: &[Card]) -> Rank {
+        rank::ace_to_five_low(up)
+    }
+
+    fn max_players(&self) -> usize {
+        8
+    }
+}
+
+/// Seven card stud with the pot split between the best high and the best eight or better low
+#[derive(Debug, Clone, Copy)]
+pub struct StudHiLo {
+    pub betting: BettingStructure,
+}
+
+impl Default for StudHiLo {
+    fn default() -> Self {
+        Self {
+            betting: BettingStructure::FixedLimit,
+        }
+    }
+}
+
+impl Variant for StudHiLo {
+    fn game_type(&self) -> GameType {
+        GameType::StudHiLo
+    }
+
+    fn name(&self) -> String {
+        "Seven Card Stud Eight or Better".to_string()
+    }
+
+    fn streets(&self) -> Vec<Street> {
+        stud_streets()
+    }
+
+    fn betting(&self) -> BettingStructure {
+        self.betting
+    }
+
+    fn forced_bets(&self) -> ForcedBets {
+        STUD_FORCED_BETS
+    }
+
+    fn evaluator(&self) -> Evaluator {
+        Evaluator::HiLo
+    }
+
+    fn max_players(&self) -> usize {
+        8
+    }
+}
@@ -0,0 +1,8 @@
+pub mod cards;
+pub mod game;
+pub mod util;
+
+pub use cards::card::*;
+pub use cards::card_stack::*;
+pub use game::actor::{LocalPlayer, NPC};
+pub use game::table::{Table, TexasHoldem};
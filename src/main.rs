@@ -1,20 +1,46 @@
-use game::actor::LocalPlayer;
-use game::actor::NPC;
-use game::table::TexasHoldem;
+use std::env;
 
-use crate::cards::card::*;
-use crate::cards::card_stack::*;
-
-mod cards;
-mod game;
-mod util;
+use bitwise_poker::game::actor::LocalPlayer;
+use bitwise_poker::game::actor::NPC;
+use bitwise_poker::game::profile::{load_profiles, Profile};
+use bitwise_poker::game::runner::{Entrant, Match};
+use bitwise_poker::game::table::TexasHoldem;
 
 fn main() {
-    let mut table = TexasHoldem::new(10, 2000, 20000);
+    let profiles = load_profiles("bots.ini").unwrap_or_else(|error| {
+        println!("Playing against one bot of every style, {}", error);
+        Profile::lineup()
+    });
+
+    // `match [hands]` lets the bots play each other without a human at the table
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("match") {
+        let hands = args
+            .get(1)
+            .and_then(|hands| hands.parse().ok())
+            .unwrap_or(10000);
+        let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let entrants = profiles
+            .into_iter()
+            .map(|profile| {
+                let name = profile.name.clone();
+                Entrant::new(&name, move |seed| {
+                    Box::new(NPC::from_profile(&profile).with_seed(seed))
+                })
+            })
+            .collect();
 
-    let bots = vec!["Alice", "Bob", "Charlie", "David", "Eve"];
-    bots.iter()
-        .for_each(|&name| table.add_player(name.to_string(), Box::new(NPC::new(name.to_string()))));
+        match Match::new(entrants, hands).with_threads(threads).run() {
+            Ok(report) => print!("{}", report),
+            Err(error) => println!("Can't play a match with bots.ini, {}", error),
+        }
+        return;
+    }
+
+    let mut table = TexasHoldem::new(10, 2000, 20000);
+    for profile in profiles.iter().take(table.seats - 1) {
+        table.add_player(profile.name.clone(), Box::new(NPC::from_profile(profile)));
+    }
 
     table.add_player("You".to_string(), Box::new(LocalPlayer));
 
@@ -0,0 +1,377 @@
+use std::{
+    cell::RefCell,
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    pin::{pin, Pin},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Sender},
+        Arc, Condvar, Mutex, OnceLock,
+    },
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+    time::Instant,
+};
+
+/// Wakes a thread that is parked in `block_on`
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Runs `future` to completion on the current thread
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+
+        thread::park();
+    }
+}
+
+#[derive(Default)]
+struct Timers {
+    /// Deadlines with the id of their waker, the earliest on top
+    queue: BinaryHeap<Reverse<(Instant, u64)>>,
+    /// Wakers of the deadlines still waited for, by id
+    wakers: HashMap<u64, Waker>,
+    next_id: u64,
+}
+
+/// Wakes the futures waiting for their deadlines, one thread for all of them
+#[derive(Default)]
+struct Timer {
+    timers: Mutex<Timers>,
+    changed: Condvar,
+}
+
+impl Timer {
+    /// The timer of the process, its thread starts with the first deadline
+    fn get() -> &'static Timer {
+        static TIMER: OnceLock<Timer> = OnceLock::new();
+
+        let mut started = false;
+        let timer = TIMER.get_or_init(|| {
+            started = true;
+            Timer::default()
+        });
+        if started {
+            thread::spawn(move || timer.run());
+        }
+
+        timer
+    }
+
+    /// Wakes `waker` at `deadline` and returns the id to update or cancel it with
+    fn add(&self, deadline: Instant, waker: Waker) -> u64 {
+        let mut timers = self.timers.lock().unwrap();
+        let id = timers.next_id;
+        timers.next_id += 1;
+        timers.queue.push(Reverse((deadline, id)));
+        timers.wakers.insert(id, waker);
+        self.changed.notify_one();
+        id
+    }
+
+    fn update(&self, id: u64, waker: &Waker) {
+        if let Some(old) = self.timers.lock().unwrap().wakers.get_mut(&id) {
+            old.clone_from(waker);
+        }
+    }
+
+    /// Without a waker the deadline wakes nobody, the queue drops it once it is mostly cancelled ones
+    fn cancel(&self, id: u64) {
+        let timers = &mut *self.timers.lock().unwrap();
+        timers.wakers.remove(&id);
+
+        if timers.queue.len() > 2 * timers.wakers.len() + 64 {
+            let wakers = &timers.wakers;
+            timers
+                .queue
+                .retain(|Reverse((_, id))| wakers.contains_key(id));
+        }
+    }
+
+    fn run(&self) {
+        let mut timers = self.timers.lock().unwrap();
+        loop {
+            let now = Instant::now();
+            let mut due = vec![];
+            while let Some(&Reverse((deadline, id))) = timers.queue.peek() {
+                if deadline > now {
+                    break;
+                }
+                timers.queue.pop();
+                due.extend(timers.wakers.remove(&id));
+            }
+
+            if !due.is_empty() {
+                // a waker may poll its future right away, which adds or cancels deadlines
+                drop(timers);
+                due.into_iter().for_each(Waker::wake);
+                timers = self.timers.lock().unwrap();
+                continue;
+            }
+
+            timers = match timers.queue.peek() {
+                Some(&Reverse((deadline, _))) => {
+                    self.changed.wait_timeout(timers, deadline - now).unwrap().0
+                }
+                None => self.changed.wait(timers).unwrap(),
+            };
+        }
+    }
+}
+
+/// Output of a future or `None` once the deadline passed, see `with_deadline`
+pub struct Deadline<F: Future> {
+    future: Pin<Box<F>>,
+    deadline: Option<Instant>,
+    /// Id of the deadline in the timer, `None` until the future had to wait
+    timer: Option<u64>,
+}
+
+/// Waits for `future` until `deadline`, forever without one.
+/// A future that misses the deadline is dropped.
+pub fn with_deadline<F: Future>(future: F, deadline: Option<Instant>) -> Deadline<F> {
+    Deadline {
+        future: Box::pin(future),
+        deadline,
+        timer: None,
+    }
+}
+
+impl<F: Future> Future for Deadline<F> {
+    type Output = Option<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Poll::Ready(output) = self.future.as_mut().poll(cx) {
+            return Poll::Ready(Some(output));
+        }
+
+        let Some(deadline) = self.deadline else {
+            return Poll::Pending;
+        };
+
+        if Instant::now() >= deadline {
+            return Poll::Ready(None);
+        }
+
+        match self.timer {
+            Some(id) => Timer::get().update(id, cx.waker()),
+            None => self.timer = Some(Timer::get().add(deadline, cx.waker().clone())),
+        }
+
+        Poll::Pending
+    }
+}
+
+impl<F: Future> Drop for Deadline<F> {
+    fn drop(&mut self) {
+        if let Some(id) = self.timer {
+            Timer::get().cancel(id);
+        }
+    }
+}
+
+struct Slot<T> {
+    value: Option<T>,
+    /// The call is over, with or without a value
+    done: bool,
+    waker: Option<Waker>,
+}
+
+/// Hands the result of a blocking call over to its future, even if the call panics or is skipped
+struct Completer<T>(Arc<Mutex<Slot<T>>>);
+
+impl<T> Drop for Completer<T> {
+    fn drop(&mut self) {
+        let mut slot = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        slot.done = true;
+        if let Some(waker) = slot.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+thread_local! {
+    /// Cancel flag of the call a worker is making on this thread
+    static CANCELLED: RefCell<Option<Arc<AtomicBool>>> = const { RefCell::new(None) };
+}
+
+/// Whether nobody waits for the blocking call running on this thread anymore, long calls can stop early
+pub fn is_cancelled() -> bool {
+    CANCELLED.with(|cancelled| {
+        cancelled
+            .borrow()
+            .as_ref()
+            .is_some_and(|cancelled| cancelled.load(Ordering::Relaxed))
+    })
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// A thread making blocking calls one after the other, see `Worker::run`.
+/// The thread ends with the worker, once it made the calls that are left
+pub struct Worker(Sender<Job>);
+
+impl Worker {
+    pub fn new() -> Self {
+        let (jobs, queue) = mpsc::channel::<Job>();
+        thread::spawn(move || queue.into_iter().for_each(|job| job()));
+        Self(jobs)
+    }
+
+    /// Queues a blocking call, the returned future completes with its result or `None` if it panicked.
+    /// Dropping the future cancels the call: it is skipped if it didn't start yet and sees
+    /// `is_cancelled` otherwise
+    pub fn run<T, F>(&self, call: F) -> Blocking<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let slot = Arc::new(Mutex::new(Slot {
+            value: None,
+            done: false,
+            waker: None,
+        }));
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let completer = Completer(Arc::clone(&slot));
+        let flag = Arc::clone(&cancelled);
+
+        let job = move || {
+            if flag.load(Ordering::Relaxed) {
+                return;
+            }
+
+            CANCELLED.with(|cancelled| *cancelled.borrow_mut() = Some(flag));
+            let value = panic::catch_unwind(AssertUnwindSafe(call)).ok();
+            CANCELLED.with(|cancelled| *cancelled.borrow_mut() = None);
+
+            completer.0.lock().unwrap_or_else(|e| e.into_inner()).value = value;
+        };
+
+        // without its thread the call is dropped undone, which completes the future with `None`
+        let _ = self.0.send(Box::new(job));
+
+        Blocking { slot, cancelled }
+    }
+}
+
+impl Default for Worker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Result of a call made by a `Worker`
+pub struct Blocking<T> {
+    slot: Arc<Mutex<Slot<T>>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+/// Runs a blocking call on its own thread, see `Worker::run`
+pub fn spawn_blocking<T, F>(call: F) -> Blocking<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    Worker::new().run(call)
+}
+
+impl<T> Future for Blocking<T> {
+    type Output = Option<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let mut slot = self.slot.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(value) = slot.value.take() {
+            return Poll::Ready(Some(value));
+        }
+
+        if slot.done {
+            return Poll::Ready(None);
+        }
+
+        slot.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl<T> Drop for Blocking<T> {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future, time::Duration};
+
+    use super::*;
+
+    #[test]
+    fn test_block_on_blocking_call() {
+        let value = block_on(spawn_blocking(|| {
+            thread::sleep(Duration::from_millis(5));
+            42
+        }));
+
+        assert_eq!(value, Some(42));
+        assert_eq!(block_on(spawn_blocking(|| panic!("buggy bot"))), None::<()>);
+    }
+
+    #[test]
+    fn test_worker_cancels_abandoned_calls() {
+        let worker = Worker::new();
+
+        // the first call only stops once its future is gone, the second never starts
+        let first = worker.run(|| {
+            while !is_cancelled() {
+                thread::sleep(Duration::from_millis(1));
+            }
+        });
+        let second = worker.run(|| panic!("Cancelled calls are skipped"));
+        let soon = Some(Instant::now() + Duration::from_millis(10));
+        assert_eq!(block_on(with_deadline(first, soon)), None);
+        drop(second);
+
+        assert_eq!(block_on(worker.run(is_cancelled)), Some(false));
+    }
+
+    #[test]
+    fn test_deadline() {
+        let soon = Some(Instant::now() + Duration::from_millis(10));
+
+        assert_eq!(block_on(with_deadline(future::pending::<()>(), soon)), None);
+        assert_eq!(block_on(with_deadline(future::ready(1), soon)), Some(1));
+        assert_eq!(block_on(with_deadline(future::ready(1), None)), Some(1));
+    }
+
+    #[test]
+    fn test_answered_deadline_is_cancelled() {
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let later = Some(Instant::now() + Duration::from_secs(3600));
+
+        let mut deadline = with_deadline(future::pending::<()>(), later);
+        assert!(Pin::new(&mut deadline)
+            .poll(&mut Context::from_waker(&waker))
+            .is_pending());
+
+        let id = deadline.timer.unwrap();
+        assert!(Timer::get().timers.lock().unwrap().wakers.contains_key(&id));
+
+        drop(deadline);
+        assert!(!Timer::get().timers.lock().unwrap().wakers.contains_key(&id));
+    }
+}
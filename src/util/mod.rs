@@ -1,20 +1,59 @@
-use std::io;
+pub mod executor;
 
-pub fn user_input() -> io::Result<String> {
-    let mut buffer = String::new();
-    io::stdin().read_line(&mut buffer)?;
-    Ok(buffer)
+use std::{
+    io,
+    sync::{
+        mpsc::{self, Receiver, RecvTimeoutError},
+        Mutex, OnceLock,
+    },
+    thread,
+    time::Duration,
+};
+
+use executor::is_cancelled;
+
+/// Lines typed on stdin, read on a thread of their own so a question
+/// the table gave up on doesn't keep waiting for the next one
+fn typed_lines() -> &'static Mutex<Receiver<String>> {
+    static LINES: OnceLock<Mutex<Receiver<String>>> = OnceLock::new();
+
+    LINES.get_or_init(|| {
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in io::stdin().lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Mutex::new(lines)
+    })
 }
 
-pub fn read_user_input() -> usize {
+/// Next line typed, `None` once stdin is closed or the question is cancelled
+pub fn user_input() -> Option<String> {
+    let lines = typed_lines().lock().unwrap();
     loop {
-        let choice = user_input().expect("Failed to read choice");
-        return match choice.trim().parse() {
-            Ok(num) => num,
-            Err(_) => {
-                println!("Please enter a number");
-                continue;
-            }
-        };
+        match lines.recv_timeout(Duration::from_millis(50)) {
+            Ok(line) => return Some(line),
+            Err(RecvTimeoutError::Timeout) if !is_cancelled() => continue,
+            Err(_) => return None,
+        }
+    }
+}
+
+/// Drops the lines typed before a question is asked, like answers to one that timed out
+pub fn forget_user_input() {
+    typed_lines().lock().unwrap().try_iter().for_each(drop);
+}
+
+pub fn read_user_input() -> Option<usize> {
+    loop {
+        let choice = user_input()?;
+        match choice.trim().parse() {
+            Ok(num) => return Some(num),
+            Err(_) => println!("Please enter a number"),
+        }
     }
 }